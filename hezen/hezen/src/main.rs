@@ -1,7 +1,10 @@
 use anyhow::Result;
 use clap::Parser;
-use hezen_core::Verbosity;
-use std::{io::Read, path::PathBuf};
+use hezen_core::{ErrorFormat, Outcome, Verbosity};
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+};
 
 #[derive(Parser, Clone, Debug)]
 #[command(version = "1.0", about = "The Hezen Language")]
@@ -15,9 +18,22 @@ struct Args {
 #[derive(Parser, Clone, Debug)]
 enum SubCommand {
     #[command(name = "run")]
-    Run { file: Option<PathBuf> },
+    Run {
+        file: Option<PathBuf>,
+        #[arg(
+            long,
+            default_value = "human",
+            value_parser = ["human", "json"],
+            help = "How to report errors: 'human' or 'json' (one object per line)"
+        )]
+        error_format: String,
+        #[arg(last = true, help = "Arguments passed to the script, after '--'")]
+        args: Vec<String>,
+    },
     #[command(name = "shell")]
     Shell,
+    #[command(name = "doc", about = "Print the doc comments of a script as Markdown")]
+    Doc { file: PathBuf },
 }
 
 fn main() -> Result<()> {
@@ -26,8 +42,19 @@ fn main() -> Result<()> {
     let verbosity = get_verbosity(args.verbosity);
 
     match args.subcmd {
-        SubCommand::Run { file } => run(file, verbosity)?,
+        SubCommand::Run {
+            file,
+            error_format,
+            args,
+        } => {
+            let error_format = match error_format.as_str() {
+                "json" => ErrorFormat::Json,
+                _ => ErrorFormat::Human,
+            };
+            run(file, args, verbosity, error_format)?
+        }
         SubCommand::Shell => shell(),
+        SubCommand::Doc { file } => doc(file)?,
     }
 
     Ok(())
@@ -55,30 +82,66 @@ fn get_verbosity(level: Option<u8>) -> Verbosity {
     v
 }
 
-fn run(file: Option<PathBuf>, verbosity: Verbosity) -> Result<()> {
-    if let Some(file) = file {
+fn run(
+    file: Option<PathBuf>,
+    args: Vec<String>,
+    verbosity: Verbosity,
+    error_format: ErrorFormat,
+) -> Result<()> {
+    let (filename, code) = if let Some(file) = file {
         let filename = file.to_str().unwrap().to_string();
-        let code = std::fs::read_to_string(file)?;
-        let result = hezen_runtime::run(filename, code.clone(), verbosity);
-
-        if let Err(err) = result {
-            let mut buffer = String::new();
-            err.print_details(&mut buffer, &*code).unwrap();
-            eprintln!("{buffer}");
-        }
+        (filename, std::fs::read_to_string(file)?)
     } else {
         let stdin = std::io::stdin();
         let mut stdin = stdin.lock();
         let mut code = String::new();
         stdin.read_to_string(&mut code)?;
-        let result = hezen_runtime::run(String::from("<stdin>"), code.clone(), verbosity);
+        (String::from("<stdin>"), code)
+    };
+
+    let result = hezen_runtime::run(filename, code.clone(), args, verbosity, error_format);
+
+    if let Ok(Outcome::Exited(code)) = result {
+        std::io::stdout().flush()?;
+        std::process::exit(code);
+    }
 
-        if let Err(err) = result {
+    if let Err(err) = result {
+        let mut buffer = String::new();
+        match error_format {
+            ErrorFormat::Human => {
+                err.print_details(&mut buffer, &*code).unwrap();
+                if !buffer.is_empty() {
+                    eprintln!("{buffer}");
+                }
+            }
+            ErrorFormat::Json => {
+                err.print_json(&mut buffer).unwrap();
+                eprint!("{buffer}");
+            }
+        }
+
+        std::io::stdout().flush()?;
+        std::process::exit(err.exit_code());
+    }
+
+    Ok(())
+}
+
+fn doc(file: PathBuf) -> Result<()> {
+    let filename = file.to_str().unwrap().to_string();
+    let code = std::fs::read_to_string(file)?;
+
+    match hezen_runtime::document(filename, code.clone()) {
+        Ok(markdown) => print!("{markdown}"),
+        Err(err) => {
             let mut buffer = String::new();
             err.print_details(&mut buffer, &*code).unwrap();
             eprintln!("{buffer}");
+            std::process::exit(err.exit_code());
         }
     }
+
     Ok(())
 }
 
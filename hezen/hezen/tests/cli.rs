@@ -0,0 +1,86 @@
+use std::process::Command;
+
+/// Runs `source` as a script named `name` with `hezen run`, returning the
+/// exit code, stdout and stderr.
+fn run(name: &str, source: &str, args: &[&str]) -> (i32, String, String) {
+    let path = std::env::temp_dir().join(format!("hezen_cli_{}_{name}", std::process::id()));
+    std::fs::write(&path, source).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_hezen"))
+        .arg("run")
+        .args(args)
+        .arg(&path)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .replace(path.to_str().unwrap(), name),
+    )
+}
+
+fn json(name: &str, source: &str) -> (i32, String, String) {
+    run(name, source, &["--error-format", "json"])
+}
+
+#[test]
+fn json_errors_span_the_token_they_point_at() {
+    let (code, stdout, stderr) = json("undefined.hez", "println(\"before\");\nprintln(count);\n");
+
+    assert_eq!(code, 70);
+    assert_eq!(stdout, "before\n");
+    assert_eq!(
+        stderr,
+        "{\"kind\":\"RuntimeError\",\"severity\":\"error\",\
+         \"message\":\"Undefined variable IN ENVIRONMENT GET 'count'\",\"file\":\"undefined.hez\",\
+         \"line\":2,\"column\":9,\"end_line\":2,\"end_column\":14,\"notes\":[]}\n"
+    );
+}
+
+#[test]
+fn json_spans_follow_tokens_over_several_lines() {
+    let (code, _, stderr) = json("multiline.hez", "var x = 1 \"two\nlines\";\n");
+
+    assert_eq!(code, 65);
+    let first = stderr.lines().next().unwrap();
+    assert!(
+        first.contains("\"line\":1,\"column\":11,\"end_line\":2,\"end_column\":7"),
+        "{first}"
+    );
+}
+
+#[test]
+fn json_errors_without_a_position_cover_one_character() {
+    let (code, _, stderr) = json("eof.hez", "var x =\n");
+
+    assert_eq!(code, 65);
+    assert!(
+        stderr.contains("\"line\":2,\"column\":1,\"end_line\":2,\"end_column\":2"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn json_reports_one_object_per_line() {
+    let (code, _, stderr) = json("two.hez", "var = 1;\nvar = 2;\n");
+
+    assert_eq!(code, 65);
+    let lines: Vec<&str> = stderr.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines
+        .iter()
+        .all(|line| line.starts_with("{\"kind\":\"SyntaxError\"") && line.ends_with('}')));
+}
+
+#[test]
+fn human_errors_are_the_default() {
+    let (code, _, stderr) = run("human.hez", "println(count);\n", &[]);
+
+    assert_eq!(code, 70);
+    assert!(!stderr.starts_with('{'), "{stderr}");
+    assert!(stderr.contains("count"), "{stderr}");
+}
@@ -3,16 +3,37 @@ use std::{error::Error, fmt::Display};
 #[cfg(feature = "color")]
 use colored::Colorize;
 
+/// Where a token is. `line` and `column` point at its last character and
+/// `start_line` and `start_column` at its first, which differ for tokens
+/// longer than one character.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct HezenLineInfo {
     pub file: String,
     pub line: usize,
     pub column: usize,
+    pub start_line: usize,
+    pub start_column: usize,
 }
 
 impl HezenLineInfo {
+    /// A position one character long.
     pub fn new(file: String, line: usize, column: usize) -> Self {
-        Self { file, line, column }
+        Self {
+            file,
+            line,
+            column,
+            start_line: line,
+            start_column: column,
+        }
+    }
+
+    /// The same position, for a token that starts at `line` and `column`.
+    pub fn starting_at(self, line: usize, column: usize) -> Self {
+        Self {
+            start_line: line,
+            start_column: column,
+            ..self
+        }
     }
 }
 
@@ -21,6 +42,17 @@ pub enum HezenError {
     Syntax(HezenLineInfo, String),
     Validation(HezenLineInfo, String),
     Runtime(HezenLineInfo, String),
+    /// A runtime failure of a more specific kind than `RuntimeError`, such as
+    /// `IOError`, that scripts can inspect when catching it.
+    RuntimeKind {
+        info: HezenLineInfo,
+        kind: String,
+        message: String,
+    },
+    Warning(HezenLineInfo, String),
+    /// The host cancelled the run, for example because Ctrl-C was pressed.
+    /// Scripts cannot catch it.
+    Interrupted(HezenLineInfo),
 }
 
 impl Display for HezenError {
@@ -36,11 +68,24 @@ impl Display for HezenError {
                 "Validation error in file {} at line {}:{}: {}",
                 info.file, info.line, info.column, msg
             ),
-            HezenError::Runtime(info, msg) => write!(
+            HezenError::Runtime(info, msg)
+            | HezenError::RuntimeKind {
+                info, message: msg, ..
+            } => write!(
                 f,
                 "Runtime error in file {} at line {}:{}: {}",
                 info.file, info.line, info.column, msg
             ),
+            HezenError::Warning(info, msg) => write!(
+                f,
+                "Warning in file {} at line {}:{}: {}",
+                info.file, info.line, info.column, msg
+            ),
+            HezenError::Interrupted(info) => write!(
+                f,
+                "Interrupted in file {} at line {}:{}",
+                info.file, info.line, info.column
+            ),
         }
     }
 }
@@ -49,15 +94,144 @@ impl Error for HezenError {}
 
 impl HezenError {
     pub fn syntax_error(file: String, line: usize, column: usize, msg: String) -> Self {
-        Self::Syntax(HezenLineInfo { file, line, column }, msg)
+        Self::Syntax(HezenLineInfo::new(file, line, column), msg)
     }
 
     pub fn validation(file: String, line: usize, column: usize, msg: String) -> Self {
-        Self::Validation(HezenLineInfo { file, line, column }, msg)
+        Self::Validation(HezenLineInfo::new(file, line, column), msg)
     }
 
     pub fn runtime(file: String, line: usize, column: usize, msg: String) -> Self {
-        Self::Runtime(HezenLineInfo { file, line, column }, msg)
+        Self::Runtime(HezenLineInfo::new(file, line, column), msg)
+    }
+
+    /// A runtime error of `kind` at `info`; `RuntimeError` gives a plain
+    /// [`HezenError::Runtime`].
+    pub fn runtime_with_kind(kind: &str, info: HezenLineInfo, msg: String) -> Self {
+        if kind == "RuntimeError" {
+            Self::Runtime(info, msg)
+        } else {
+            Self::RuntimeKind {
+                info,
+                kind: kind.to_string(),
+                message: msg,
+            }
+        }
+    }
+
+    pub fn warning(file: String, line: usize, column: usize, msg: String) -> Self {
+        Self::Warning(HezenLineInfo::new(file, line, column), msg)
+    }
+
+    pub fn is_warning(&self) -> bool {
+        matches!(self, HezenError::Warning(..))
+    }
+
+    /// Whether the error was raised while running, of whatever kind.
+    pub fn is_runtime(&self) -> bool {
+        matches!(
+            self,
+            HezenError::Runtime(..) | HezenError::RuntimeKind { .. }
+        )
+    }
+
+    pub fn info(&self) -> &HezenLineInfo {
+        match self {
+            HezenError::Syntax(info, _)
+            | HezenError::Validation(info, _)
+            | HezenError::Runtime(info, _)
+            | HezenError::RuntimeKind { info, .. }
+            | HezenError::Warning(info, _)
+            | HezenError::Interrupted(info) => info,
+        }
+    }
+
+    pub fn kind(&self) -> &str {
+        match self {
+            HezenError::Syntax(..) => "SyntaxError",
+            HezenError::Validation(..) => "ValidationError",
+            HezenError::Runtime(..) => "RuntimeError",
+            HezenError::RuntimeKind { kind, .. } => kind,
+            HezenError::Warning(..) => "Warning",
+            HezenError::Interrupted(..) => "Interrupted",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            HezenError::Syntax(_, msg)
+            | HezenError::Validation(_, msg)
+            | HezenError::Runtime(_, msg)
+            | HezenError::Warning(_, msg)
+            | HezenError::RuntimeKind { message: msg, .. } => msg,
+            HezenError::Interrupted(..) => "Execution interrupted",
+        }
+    }
+
+    /// Whether the error is reported as a warning or an error.
+    pub fn severity(&self) -> &str {
+        if self.is_warning() {
+            "warning"
+        } else {
+            "error"
+        }
+    }
+
+    /// Renders the error as a single line of JSON:
+    ///
+    /// ```
+    /// use hezen_core::error::{HezenError, HezenLineInfo};
+    ///
+    /// let at = HezenLineInfo::new("main.hez".to_string(), 1, 13).starting_at(1, 9);
+    /// let error = HezenError::Runtime(at, "Undefined variable 'count'".to_string());
+    /// assert_eq!(
+    ///     error.to_json(),
+    ///     "{\"kind\":\"RuntimeError\",\"severity\":\"error\",\"message\":\"Undefined variable 'count'\",\
+    ///      \"file\":\"main.hez\",\"line\":1,\"column\":9,\"end_line\":1,\"end_column\":14,\"notes\":[]}"
+    /// );
+    /// ```
+    ///
+    /// Lines and columns count from 1. The span runs from the first character
+    /// of the token the error points at to just past its last, so
+    /// `end_column` is one past the end; errors without a position cover a
+    /// single character. `notes` holds extra explanations and is empty for the
+    /// errors Hezen reports today.
+    pub fn to_json(&self) -> String {
+        let info = self.info();
+        let end_column = info.column.max(1) + 1;
+        let column = if info.start_line == info.line {
+            info.start_column.clamp(1, end_column - 1)
+        } else {
+            info.start_column.max(1)
+        };
+
+        format!(
+            "{{\"kind\":{},\"severity\":{},\"message\":{},\"file\":{},\"line\":{},\"column\":{},\"end_line\":{},\"end_column\":{},\"notes\":[]}}",
+            json_string(self.kind()),
+            json_string(self.severity()),
+            json_string(self.message()),
+            json_string(&info.file),
+            info.start_line,
+            column,
+            info.line,
+            end_column,
+        )
+    }
+
+    /// Moves the error to another position, keeping its kind and message.
+    pub fn at(self, info: HezenLineInfo) -> Self {
+        match self {
+            HezenError::Syntax(_, msg) => HezenError::Syntax(info, msg),
+            HezenError::Validation(_, msg) => HezenError::Validation(info, msg),
+            HezenError::Runtime(_, msg) => HezenError::Runtime(info, msg),
+            HezenError::RuntimeKind { kind, message, .. } => HezenError::RuntimeKind {
+                info,
+                kind,
+                message,
+            },
+            HezenError::Warning(_, msg) => HezenError::Warning(info, msg),
+            HezenError::Interrupted(_) => HezenError::Interrupted(info),
+        }
     }
 
     #[cfg(feature = "color")]
@@ -73,8 +247,13 @@ impl HezenError {
             HezenError::Validation(info, msg) => {
                 self.print_internal(f, source, info, "validation error", msg)
             }
-            HezenError::Runtime(info, msg) => {
-                self.print_internal(f, source, info, "runtime error", msg)
+            HezenError::Runtime(info, msg)
+            | HezenError::RuntimeKind {
+                info, message: msg, ..
+            } => self.print_internal(f, source, info, "runtime error", msg),
+            HezenError::Warning(info, msg) => self.print_internal(f, source, info, "warning", msg),
+            HezenError::Interrupted(info) => {
+                self.print_internal(f, source, info, "interrupted", self.message())
             }
         }
     }
@@ -92,7 +271,11 @@ impl HezenError {
         let lines = source.lines().collect::<Vec<&str>>();
         let line_max_len = lines.len().to_string().len();
 
-        writeln!(f, "{}: {}", prefix.red(), msg)?;
+        if self.is_warning() {
+            writeln!(f, "{}: {}", prefix.yellow(), msg)?;
+        } else {
+            writeln!(f, "{}: {}", prefix.red(), msg)?;
+        }
         writeln!(
             f,
             "{} {}:{}:{}",
@@ -101,7 +284,10 @@ impl HezenError {
             info.line,
             info.column
         )?;
-        let line = lines[info.line - 1];
+        let line = lines
+            .get(info.line.saturating_sub(1))
+            .copied()
+            .unwrap_or_default();
         let line_num = info.line.to_string();
         let line_num = format!("{line_num: >line_max_len$} |").bright_blue();
 
@@ -129,14 +315,49 @@ impl HezenErrorList {
         self.errors.is_empty()
     }
 
+    /// Removes and returns the warnings, leaving only the errors behind.
+    pub fn take_warnings(&mut self) -> HezenErrorList {
+        let (warnings, errors) = std::mem::take(&mut self.errors)
+            .into_iter()
+            .partition(HezenError::is_warning);
+
+        self.errors = errors;
+
+        HezenErrorList { errors: warnings }
+    }
+
     pub fn len(&self) -> usize {
         self.errors.len()
     }
 
+    /// The process exit status for these errors: 65 for problems found
+    /// before running, 70 for runtime errors and 130 for an interrupted run.
+    pub fn exit_code(&self) -> i32 {
+        if self
+            .errors
+            .iter()
+            .any(|error| matches!(error, HezenError::Interrupted(..)))
+        {
+            130
+        } else if self.errors.iter().any(HezenError::is_runtime) {
+            70
+        } else {
+            65
+        }
+    }
+
     pub fn iter(&self) -> std::slice::Iter<'_, HezenError> {
         self.errors.iter()
     }
 
+    /// Writes each error as a line of JSON, see [`HezenError::to_json`].
+    pub fn print_json(&self, mut f: impl std::fmt::Write) -> std::fmt::Result {
+        for error in &self.errors {
+            writeln!(f, "{}", error.to_json())?;
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "color")]
     pub fn print_details<'a>(
         &self,
@@ -161,6 +382,15 @@ impl Display for HezenErrorList {
 
 impl Error for HezenErrorList {}
 
+impl IntoIterator for HezenErrorList {
+    type Item = HezenError;
+    type IntoIter = std::vec::IntoIter<HezenError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
 impl From<HezenError> for HezenErrorList {
     fn from(error: HezenError) -> Self {
         Self {
@@ -168,3 +398,21 @@ impl From<HezenError> for HezenErrorList {
         }
     }
 }
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
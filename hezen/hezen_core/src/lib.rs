@@ -5,3 +5,33 @@ pub struct Verbosity {
     pub intermediate: bool,
     pub resolver: bool,
 }
+
+/// How errors and warnings are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorFormat {
+    /// Source excerpts for people, colored when the `color` feature is on.
+    #[default]
+    Human,
+    /// One JSON object per line, for editors and CI tools.
+    Json,
+}
+
+/// How a run that raised no error ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Outcome {
+    /// The script ran to its end.
+    #[default]
+    Finished,
+    /// The script called `exit` with this code.
+    Exited(i32),
+}
+
+impl Outcome {
+    /// The process exit status for the outcome.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Outcome::Finished => 0,
+            Outcome::Exited(code) => code,
+        }
+    }
+}
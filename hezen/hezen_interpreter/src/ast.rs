@@ -1,28 +1,75 @@
 use std::fmt::Display;
 
-use crate::token::Token;
+use num_bigint::BigInt;
+use rust_decimal::Decimal;
+
+use crate::token::{Token, TokenType};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Expr {
     Assign(Token, Box<Expr>),
     Binary(Box<Expr>, Token, Box<Expr>),
     Call(Box<Expr>, Token, Vec<Expr>),
+    CompoundAssign(Token, Token, Box<Expr>),
+    CompoundSet(Box<Expr>, Token, Token, Box<Expr>),
+    CompoundSetIndex(Box<Expr>, Token, Box<Expr>, Token, Box<Expr>),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
     Get(Box<Expr>, Token),
     Grouping(Box<Expr>),
+    Index(Box<Expr>, Token, Box<Expr>),
+    Interpolation(Token, Vec<Expr>),
+    List(Token, Vec<Expr>),
     Literal(Literal),
     Logical(Box<Expr>, Token, Box<Expr>),
+    Map(Token, Vec<(Expr, Expr)>),
+    Match(Token, Box<Expr>, Vec<MatchArm>),
+    OptionalGet(Box<Expr>, Token),
+    /// A `++` or `--` after its target: the compound assignment it stands
+    /// for, evaluating to the value from before it.
+    Postfix(Box<Expr>),
     Self_(Token),
     Super(Token, Token),
     Set(Box<Expr>, Token, Box<Expr>),
+    SetIndex(Box<Expr>, Token, Box<Expr>, Box<Expr>),
+    Tuple(Token, Vec<Expr>),
     Unary(Token, Box<Expr>),
     Variable(Token),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    Wildcard(Token),
+    Literal(Token, Literal),
+    Range(Token, Literal, Literal, bool),
+    Binding(Token),
+    Instance(Token, Vec<(Token, Option<Pattern>)>),
+    Variant(Token, Token, Option<Vec<Pattern>>),
+    Alternatives(Vec<Pattern>),
+}
+
+/// The left-hand side of a declaration: a plain name, or a tuple/list shape
+/// that takes its values apart, optionally collecting the rest into a list.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Destructure {
+    Name(Token),
+    Sequence(Token, Vec<Destructure>, Option<Token>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Box<Stmt>,
+}
+
 #[derive(Debug, Clone)]
 pub enum Literal {
     Nil,
     Bool(bool),
     Number(f64),
+    Integer(i64),
+    BigInt(BigInt),
+    Decimal(Decimal),
     String(String),
 }
 
@@ -31,6 +78,9 @@ impl PartialEq for Literal {
         match (self, other) {
             (Self::Bool(l0), Self::Bool(r0)) => l0 == r0,
             (Self::Number(l0), Self::Number(r0)) => l0 == r0,
+            (Self::Integer(l0), Self::Integer(r0)) => l0 == r0,
+            (Self::BigInt(l0), Self::BigInt(r0)) => l0 == r0,
+            (Self::Decimal(l0), Self::Decimal(r0)) => l0 == r0,
             (Self::String(l0), Self::String(r0)) => l0 == r0,
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
         }
@@ -43,6 +93,9 @@ impl std::hash::Hash for Literal {
         match self {
             Self::Bool(v) => v.hash(state),
             Self::Number(v) => v.to_bits().hash(state),
+            Self::Integer(v) => v.hash(state),
+            Self::BigInt(v) => v.hash(state),
+            Self::Decimal(v) => v.hash(state),
             Self::String(v) => v.hash(state),
             _ => {}
         }
@@ -51,21 +104,76 @@ impl std::hash::Hash for Literal {
 
 impl Eq for Literal {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Stmt {
     Block(Vec<Stmt>),
-    Class(Token, Option<Expr>, Vec<Stmt>),
+    Class(Token, Option<Expr>, Vec<Stmt>, Option<String>),
+    Destructure(Destructure, Expr, bool),
+    Enum(Token, Vec<(Token, Vec<Token>)>),
     Expression(Expr),
-    Function(Token, Vec<Token>, Box<Stmt>),
+    Function(Token, Vec<Token>, Box<Stmt>, Option<String>),
     If(Expr, Box<Stmt>, Option<Box<Stmt>>),
     Var(Token, Option<Expr>),
     VarMut(Token, Option<Expr>),
-    While(Expr, Box<Stmt>),
+    While(Token, Expr, Box<Stmt>),
     Return(Token, Option<Expr>),
+    Try(Box<Stmt>, Option<Token>, Box<Stmt>),
     Break,
     Continue,
 }
 
+impl Expr {
+    /// A token to report the expression at. Literals carry none.
+    pub fn token(&self) -> Option<&Token> {
+        match self {
+            Expr::Assign(name, _)
+            | Expr::CompoundAssign(name, _, _)
+            | Expr::CompoundSet(_, name, _, _)
+            | Expr::Get(_, name)
+            | Expr::OptionalGet(_, name)
+            | Expr::Set(_, name, _)
+            | Expr::Variable(name) => Some(name),
+            Expr::Binary(_, token, _)
+            | Expr::Call(_, token, _)
+            | Expr::CompoundSetIndex(_, token, _, _, _)
+            | Expr::Index(_, token, _)
+            | Expr::Interpolation(token, _)
+            | Expr::List(token, _)
+            | Expr::Logical(_, token, _)
+            | Expr::Map(token, _)
+            | Expr::Match(token, _, _)
+            | Expr::Self_(token)
+            | Expr::Super(token, _)
+            | Expr::SetIndex(_, token, _, _)
+            | Expr::Tuple(token, _)
+            | Expr::Unary(token, _) => Some(token),
+            Expr::Conditional(condition, _, _) => condition.token(),
+            Expr::Grouping(expr) | Expr::Postfix(expr) => expr.token(),
+            Expr::Literal(_) => None,
+        }
+    }
+}
+
+impl Stmt {
+    /// A token to report the statement at, if it has one.
+    pub fn token(&self) -> Option<&Token> {
+        match self {
+            Stmt::Block(stmts) => stmts.first().and_then(Stmt::token),
+            Stmt::Class(name, _, _, _)
+            | Stmt::Enum(name, _)
+            | Stmt::Function(name, _, _, _)
+            | Stmt::Var(name, _)
+            | Stmt::VarMut(name, _)
+            | Stmt::Return(name, _)
+            | Stmt::While(name, _, _) => Some(name),
+            Stmt::Destructure(_, expr, _) | Stmt::Expression(expr) => expr.token(),
+            Stmt::If(condition, body, _) => condition.token().or_else(|| body.token()),
+            Stmt::Try(body, _, _) => body.token(),
+            Stmt::Break | Stmt::Continue => None,
+        }
+    }
+}
+
 macro_rules! wrap_expr {
     ($name:expr, $($expr:expr),*) => {
         wrap_expr_in_parentheses($name, vec![$($expr),*])
@@ -91,14 +199,73 @@ impl Display for Expr {
                 callee,
                 wrap_expr_in_parentheses("arguments", arguments.iter().map(Some).collect())
             ),
+            Expr::CompoundAssign(name, op, value) => {
+                write!(
+                    f,
+                    "(compound assign {} {} {})",
+                    name.lexeme, op.lexeme, value
+                )
+            }
+            Expr::CompoundSet(object, name, op, value) => write!(
+                f,
+                "(compound set {} {} {} {})",
+                object, name.lexeme, op.lexeme, value
+            ),
+            Expr::CompoundSetIndex(object, _, index, op, value) => write!(
+                f,
+                "(compound set index {} {} {} {})",
+                object, index, op.lexeme, value
+            ),
+            Expr::Conditional(condition, then_expr, else_expr) => write!(
+                f,
+                "{}",
+                wrap_expr!(
+                    "conditional",
+                    Some(condition),
+                    Some(then_expr),
+                    Some(else_expr)
+                )
+            ),
             Expr::Get(object, name) => write!(f, "(get {} {})", object, name.lexeme),
             Expr::Grouping(expr) => write!(f, "{}", wrap_expr!("grouping", Some(expr))),
+            Expr::Index(object, _, index) => write!(f, "(index {object} {index})"),
+            Expr::Interpolation(_, parts) => write!(
+                f,
+                "{}",
+                wrap_expr_in_parentheses("interpolation", parts.iter().map(Some).collect())
+            ),
+            Expr::List(_, elements) => write!(
+                f,
+                "{}",
+                wrap_expr_in_parentheses("list", elements.iter().map(Some).collect())
+            ),
             Expr::Literal(literal) => write!(f, "{literal}"),
             Expr::Logical(left, op, right) => write!(
                 f,
                 "{}",
                 wrap_expr!(&*format!("logical {} ", op.lexeme), Some(left), Some(right))
             ),
+            Expr::Map(_, entries) => write!(
+                f,
+                "(map{})",
+                entries
+                    .iter()
+                    .map(|(key, value)| format!(" ({key} {value})"))
+                    .collect::<String>()
+            ),
+            Expr::Match(_, subject, arms) => write!(
+                f,
+                "(match {} {})",
+                subject,
+                arms.iter()
+                    .map(|arm| arm.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            Expr::OptionalGet(object, name) => {
+                write!(f, "(optional get {} {})", object, name.lexeme)
+            }
+            Expr::Postfix(assignment) => write!(f, "(postfix {assignment})"),
             Expr::Self_(_) => write!(f, "self",),
             Expr::Super(keyword, method) => {
                 write!(f, "(super {} {})", keyword.lexeme, method.lexeme)
@@ -106,18 +273,127 @@ impl Display for Expr {
             Expr::Set(object, name, value) => {
                 write!(f, "(set {} {} to {})", object, name.lexeme, value)
             }
+            Expr::SetIndex(object, _, index, value) => {
+                write!(f, "(set index {object} {index} to {value})")
+            }
+            Expr::Tuple(_, elements) => write!(
+                f,
+                "{}",
+                wrap_expr_in_parentheses("tuple", elements.iter().map(Some).collect())
+            ),
             Expr::Unary(op, right) => write!(f, "{}", wrap_expr!(&*op.lexeme, Some(right))),
             Expr::Variable(name) => write!(f, "(variable {})", name.lexeme),
         }
     }
 }
 
+impl Display for Destructure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Destructure::Name(name) => write!(f, "{}", name.lexeme),
+            Destructure::Sequence(open, targets, rest) => {
+                let mut parts = targets
+                    .iter()
+                    .map(|t| t.to_string())
+                    .collect::<Vec<String>>();
+
+                if let Some(rest) = rest {
+                    parts.push(format!("...{}", rest.lexeme));
+                }
+
+                if open.ty == TokenType::LeftBracket {
+                    write!(f, "[{}]", parts.join(", "))
+                } else {
+                    write!(f, "({})", parts.join(", "))
+                }
+            }
+        }
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pattern::Wildcard(_) => write!(f, "_"),
+            Pattern::Literal(_, literal) => write!(f, "{literal}"),
+            Pattern::Range(_, start, end, inclusive) => {
+                write!(
+                    f,
+                    "{}{}{}",
+                    start,
+                    if *inclusive { "..=" } else { ".." },
+                    end
+                )
+            }
+            Pattern::Binding(name) => write!(f, "{}", name.lexeme),
+            Pattern::Instance(class, fields) => write!(
+                f,
+                "{} {{ {} }}",
+                class.lexeme,
+                fields
+                    .iter()
+                    .map(|(name, pattern)| match pattern {
+                        Some(pattern) => format!("{}: {}", name.lexeme, pattern),
+                        None => name.lexeme.clone(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Pattern::Variant(enumeration, variant, payload) => {
+                write!(f, "{}.{}", enumeration.lexeme, variant.lexeme)?;
+
+                if let Some(payload) = payload {
+                    write!(
+                        f,
+                        "({})",
+                        payload
+                            .iter()
+                            .map(|p| p.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    )?;
+                }
+
+                Ok(())
+            }
+            Pattern::Alternatives(patterns) => write!(
+                f,
+                "{}",
+                patterns
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" | ")
+            ),
+        }
+    }
+}
+
+impl Display for MatchArm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "(arm {}{} {})",
+            self.pattern,
+            if let Some(guard) = &self.guard {
+                format!(" if {guard}")
+            } else {
+                "".to_string()
+            },
+            self.body
+        )
+    }
+}
+
 impl Display for Literal {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Literal::Nil => write!(f, "nil"),
             Literal::Bool(b) => write!(f, "{b}"),
             Literal::Number(n) => write!(f, "{n}"),
+            Literal::Integer(n) => write!(f, "{n}"),
+            Literal::BigInt(n) => write!(f, "{n}"),
+            Literal::Decimal(n) => write!(f, "{n}d"),
             Literal::String(s) => write!(f, "\"{s}\""),
         }
     }
@@ -131,7 +407,7 @@ impl Display for Stmt {
                 "{}",
                 wrap_stmt_in_parentheses("block", statements.iter().collect())
             ),
-            Stmt::Class(name, superclass, methods) => write!(
+            Stmt::Class(name, superclass, methods, _) => write!(
                 f,
                 "(class name: {}{} {})",
                 name.lexeme,
@@ -142,8 +418,37 @@ impl Display for Stmt {
                 },
                 wrap_stmt_in_parentheses("methods", methods.iter().collect())
             ),
+            Stmt::Enum(name, variants) => write!(
+                f,
+                "(enum name: {} {})",
+                name.lexeme,
+                variants
+                    .iter()
+                    .map(|(variant, fields)| if fields.is_empty() {
+                        variant.lexeme.clone()
+                    } else {
+                        format!(
+                            "({} {})",
+                            variant.lexeme,
+                            fields
+                                .iter()
+                                .map(|f| f.lexeme.clone())
+                                .collect::<Vec<String>>()
+                                .join(" ")
+                        )
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            Stmt::Destructure(target, value, mutable) => write!(
+                f,
+                "(var{} {} = {})",
+                if *mutable { " mut" } else { "" },
+                target,
+                value
+            ),
             Stmt::Expression(expr) => write!(f, "{expr}"),
-            Stmt::Function(name, params, body) => write!(
+            Stmt::Function(name, params, body, _) => write!(
                 f,
                 "(function name: {}{} {})",
                 name.lexeme,
@@ -192,7 +497,7 @@ impl Display for Stmt {
                     "".to_string()
                 }
             ),
-            Stmt::While(condition, body) => write!(f, "(while {condition} {body})"),
+            Stmt::While(_, condition, body) => write!(f, "(while {condition} {body})"),
             Stmt::Return(_, value) => write!(
                 f,
                 "(return{})",
@@ -202,6 +507,17 @@ impl Display for Stmt {
                     "".to_string()
                 }
             ),
+            Stmt::Try(body, name, handler) => write!(
+                f,
+                "(try {} catch{} {})",
+                body,
+                if let Some(name) = name {
+                    format!(" {}", name.lexeme)
+                } else {
+                    "".to_string()
+                },
+                handler
+            ),
             Stmt::Break => write!(f, "break"),
             Stmt::Continue => write!(f, "continue"),
         }
@@ -0,0 +1,56 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use hezen_core::error::HezenError;
+
+use crate::token::Token;
+
+/// Lets a host stop a running script from outside, for example from another
+/// thread or a Ctrl-C handler. Clones share the same flag.
+///
+/// The interpreter checks the token on every loop iteration and function
+/// call, and aborts the run with an uncatchable
+/// [`HezenError::Interrupted`](crate::HezenError::Interrupted). Aborting a run
+/// clears the token again.
+///
+/// Natives that work through large values, such as `json.stringify`,
+/// `json.parse`, `show`, string interpolation and the `Regex` methods, check
+/// it as they go. A native that is blocked, such as `read_line` waiting for
+/// input, only stops once it returns, and a single arithmetic operation is
+/// never interrupted.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks the current run, or the next one if none is running, to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Withdraws a cancellation that has not stopped a run yet.
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::SeqCst);
+    }
+
+    /// Fails with an `Interrupted` error at `at` if the token was cancelled,
+    /// clearing it.
+    pub(crate) fn check(&self, at: &Token) -> Result<(), HezenError> {
+        if self.cancelled.swap(false, Ordering::SeqCst) {
+            Err(HezenError::Interrupted(at.position.clone()))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashSet;
+
+use crate::interpreter::Interpreter;
+
+/// A group of natives that reach outside the interpreter. Natives that only
+/// compute, such as `len`, `math` or `json`, are always available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// `print` and `println`.
+    Print,
+    /// `input` and `read_line`.
+    Stdin,
+    /// `read_file`, `read_lines`, `write_file`, `append_file`, `remove_file`,
+    /// `file_exists` and `list_dir`.
+    Filesystem,
+    /// `clock`.
+    Clock,
+    /// `args` and `env`.
+    Environment,
+    /// `exit`.
+    Exit,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 6] = [
+        Capability::Print,
+        Capability::Stdin,
+        Capability::Filesystem,
+        Capability::Clock,
+        Capability::Environment,
+        Capability::Exit,
+    ];
+}
+
+/// Builds an [`Interpreter`] with only the natives a host opts into. It starts
+/// with no capabilities at all.
+///
+/// ```
+/// use hezen_interpreter::{Capability, Engine, InterpreterBuilder};
+///
+/// let interpreter = InterpreterBuilder::new()
+///     .allow(Capability::Print)
+///     .build();
+/// let mut engine = Engine::with_interpreter(interpreter);
+///
+/// assert!(engine.eval("println(len(\"sandboxed\"));").is_ok());
+/// assert!(engine.eval("read_file(\"/etc/passwd\");").is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InterpreterBuilder {
+    capabilities: HashSet<Capability>,
+    args: Vec<String>,
+}
+
+impl InterpreterBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allow(mut self, capability: Capability) -> Self {
+        self.capabilities.insert(capability);
+        self
+    }
+
+    pub fn allow_all(mut self) -> Self {
+        self.capabilities.extend(Capability::ALL);
+        self
+    }
+
+    /// Sets the arguments the `args` native returns.
+    pub fn args(mut self, args: Vec<String>) -> Self {
+        self.args = args;
+        self
+    }
+
+    pub fn build(self) -> Interpreter {
+        let mut interpreter = Interpreter::with_capabilities(&self.capabilities);
+        interpreter.set_args(self.args);
+        interpreter
+    }
+}
@@ -1,12 +1,13 @@
 use std::{collections::HashMap, rc::Rc};
 
-use hezen_core::error::HezenError;
+use hezen_core::error::{HezenError, HezenLineInfo};
 
 use crate::{
     environment::HezenValue,
-    function::{HezenCallable, HezenFunction},
+    function::{HezenCallable, HezenFunction, HezenNativeFunction},
     instance::HezenInstanceHandle,
     interpreter::Interpreter,
+    token::{Token, TokenType},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,6 +22,8 @@ pub struct HezenClass {
     pub name: String,
     pub superclass: Option<Rc<HezenClass>>,
     pub methods: HashMap<String, Rc<HezenFunction>>,
+    pub native_methods: HashMap<String, Rc<HezenNativeMethod>>,
+    pub doc: Option<String>,
 }
 
 impl HezenClass {
@@ -28,21 +31,70 @@ impl HezenClass {
         name: String,
         superclass: Option<Rc<HezenClass>>,
         methods: HashMap<String, Rc<HezenFunction>>,
+        doc: Option<String>,
     ) -> Self {
         Self {
             name,
             superclass,
             methods,
+            native_methods: HashMap::new(),
+            doc,
         }
     }
 
-    pub fn find_method(&self, name: &str) -> Option<Rc<HezenFunction>> {
-        match self.methods.get(name) {
-            Some(m) => Some(m.clone()),
-            None => match &self.superclass {
-                Some(sc) => sc.find_method(name),
-                None => None,
-            },
+    /// Starts a class implemented by the host. Its `init` method usually
+    /// attaches Rust data to the instance with
+    /// [`HezenInstanceHandle::set_data`]; script classes can extend it and
+    /// call `super.init(...)`.
+    pub fn native(name: &str) -> Self {
+        Self::new(name.to_string(), None, HashMap::new(), None)
+    }
+
+    /// Adds a method implemented in Rust, which receives the instance it was
+    /// called on.
+    pub fn method(
+        mut self,
+        name: &str,
+        arity: usize,
+        function: impl Fn(
+                &mut Interpreter,
+                &Token,
+                &HezenInstanceHandle,
+                &[HezenValue],
+            ) -> Result<HezenValue, HezenError>
+            + 'static,
+    ) -> Self {
+        self.native_methods.insert(
+            name.to_string(),
+            Rc::new(HezenNativeMethod {
+                name: format!("{}.{}", self.name, name),
+                arity,
+                function: Rc::new(function),
+            }),
+        );
+        self
+    }
+
+    pub fn is_subclass_of(&self, other: &HezenClass) -> bool {
+        self == other
+            || match &self.superclass {
+                Some(sc) => sc.is_subclass_of(other),
+                None => false,
+            }
+    }
+
+    pub fn find_method(&self, name: &str) -> Option<HezenMethod> {
+        if let Some(m) = self.methods.get(name) {
+            return Some(HezenMethod::Function(m.clone()));
+        }
+
+        if let Some(m) = self.native_methods.get(name) {
+            return Some(HezenMethod::Native(m.clone()));
+        }
+
+        match &self.superclass {
+            Some(sc) => sc.find_method(name),
+            None => None,
         }
     }
 }
@@ -52,6 +104,7 @@ impl PartialEq for HezenClass {
         self.name == other.name
             && self.superclass == other.superclass
             && self.methods == other.methods
+            && self.native_methods == other.native_methods
     }
 }
 
@@ -59,24 +112,29 @@ impl HezenCallable for Rc<HezenClass> {
     fn call(
         &self,
         interpreter: &mut Interpreter,
+        paren: &Token,
         arguments: &[HezenValue],
     ) -> Result<HezenValue, HezenError> {
-        if let Some(init) = self.find_method("init") {
-            let instance = HezenInstanceHandle::new(self.clone());
-
-            init.bind(instance.clone()).call(interpreter, arguments)?;
+        let instance = HezenInstanceHandle::new(self.clone());
 
-            Ok(HezenValue::Instance(instance))
-        } else {
-            Ok(HezenValue::Instance(HezenInstanceHandle::new(self.clone())))
+        match self.find_method("init") {
+            Some(HezenMethod::Function(init)) => {
+                init.bind(instance.clone())
+                    .call(interpreter, paren, arguments)?;
+            }
+            Some(HezenMethod::Native(init)) => {
+                init.call(interpreter, paren, &instance, arguments)?;
+            }
+            None => {}
         }
+
+        Ok(HezenValue::Instance(instance))
     }
 
     fn arity(&self) -> usize {
-        if let Some(init) = self.methods.get("init") {
-            init.arity()
-        } else {
-            0
+        match self.find_method("init") {
+            Some(init) => init.arity(),
+            None => 0,
         }
     }
 
@@ -84,3 +142,98 @@ impl HezenCallable for Rc<HezenClass> {
         self.name.clone()
     }
 }
+
+/// The body of a native method: it gets the interpreter, the token of the
+/// call, the instance and the arguments.
+pub type NativeMethodFn = dyn Fn(
+    &mut Interpreter,
+    &Token,
+    &HezenInstanceHandle,
+    &[HezenValue],
+) -> Result<HezenValue, HezenError>;
+
+/// A method of a native class.
+#[derive(Clone)]
+pub struct HezenNativeMethod {
+    pub name: String,
+    pub arity: usize,
+    function: Rc<NativeMethodFn>,
+}
+
+impl HezenNativeMethod {
+    pub fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        paren: &Token,
+        instance: &HezenInstanceHandle,
+        arguments: &[HezenValue],
+    ) -> Result<HezenValue, HezenError> {
+        (self.function)(interpreter, paren, instance, arguments)
+    }
+
+    fn token(&self) -> Token {
+        Token::new(
+            TokenType::Builtin,
+            self.name.clone(),
+            HezenLineInfo::new("<builtin>".to_string(), 0, 0),
+        )
+    }
+}
+
+impl PartialEq for HezenNativeMethod {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && Rc::ptr_eq(&self.function, &other.function)
+    }
+}
+
+impl std::fmt::Debug for HezenNativeMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HezenNativeMethod")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .finish()
+    }
+}
+
+/// A method found on a class, written either in Hezen or in Rust.
+#[derive(Debug, Clone)]
+pub enum HezenMethod {
+    Function(Rc<HezenFunction>),
+    Native(Rc<HezenNativeMethod>),
+}
+
+impl HezenMethod {
+    /// Turns the method into a value that remembers `instance` as `self`.
+    pub fn bind(&self, instance: HezenInstanceHandle) -> HezenValue {
+        match self {
+            HezenMethod::Function(function) => {
+                HezenValue::Function(Rc::new(function.bind(instance)))
+            }
+            HezenMethod::Native(method) => {
+                let bound = method.clone();
+
+                HezenValue::NativeFunction(Rc::new(HezenNativeFunction::new(
+                    method.token(),
+                    method.arity,
+                    move |interpreter, paren, arguments| {
+                        bound.call(interpreter, paren, &instance, arguments)
+                    },
+                )))
+            }
+        }
+    }
+
+    pub fn arity(&self) -> usize {
+        match self {
+            HezenMethod::Function(function) => function.arity(),
+            HezenMethod::Native(method) => method.arity,
+        }
+    }
+
+    pub fn doc(&self) -> Option<String> {
+        match self {
+            HezenMethod::Function(function) => function.doc.clone(),
+            HezenMethod::Native(_) => None,
+        }
+    }
+}
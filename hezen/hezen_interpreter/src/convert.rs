@@ -0,0 +1,348 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use hezen_core::error::HezenError;
+use indexmap::IndexMap;
+use num_bigint::BigInt;
+
+use crate::{environment::HezenValue, module::native_error, numeric, token::Token};
+
+/// Rust values that can be handed to scripts.
+pub trait IntoHezen {
+    fn into_hezen(self) -> HezenValue;
+}
+
+/// Rust values that can be read back from script values.
+pub trait FromHezen: Sized {
+    /// Describes the accepted values in error messages, e.g. "a number".
+    fn expected() -> String;
+
+    /// Converts `value`, or returns `None` if it is not of the right type.
+    fn from_hezen(value: &HezenValue) -> Option<Self>;
+}
+
+impl IntoHezen for HezenValue {
+    fn into_hezen(self) -> HezenValue {
+        self
+    }
+}
+
+impl FromHezen for HezenValue {
+    fn expected() -> String {
+        "any value".to_string()
+    }
+
+    fn from_hezen(value: &HezenValue) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+impl IntoHezen for () {
+    fn into_hezen(self) -> HezenValue {
+        HezenValue::Nil
+    }
+}
+
+impl IntoHezen for bool {
+    fn into_hezen(self) -> HezenValue {
+        HezenValue::Bool(self)
+    }
+}
+
+impl FromHezen for bool {
+    fn expected() -> String {
+        "a bool".to_string()
+    }
+
+    fn from_hezen(value: &HezenValue) -> Option<Self> {
+        match value {
+            HezenValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! integer_conversions {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoHezen for $ty {
+                fn into_hezen(self) -> HezenValue {
+                    match i64::try_from(self) {
+                        Ok(i) => HezenValue::Integer(i),
+                        Err(_) => HezenValue::BigInt(BigInt::from(self)),
+                    }
+                }
+            }
+
+            impl FromHezen for $ty {
+                fn expected() -> String {
+                    format!("an integer that fits in {}", stringify!($ty))
+                }
+
+                fn from_hezen(value: &HezenValue) -> Option<Self> {
+                    match value {
+                        HezenValue::Integer(i) => <$ty>::try_from(*i).ok(),
+                        HezenValue::BigInt(b) => <$ty>::try_from(b).ok(),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+integer_conversions!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl IntoHezen for f64 {
+    fn into_hezen(self) -> HezenValue {
+        HezenValue::Number(self)
+    }
+}
+
+impl FromHezen for f64 {
+    fn expected() -> String {
+        "a number".to_string()
+    }
+
+    fn from_hezen(value: &HezenValue) -> Option<Self> {
+        numeric::to_f64(value)
+    }
+}
+
+impl IntoHezen for f32 {
+    fn into_hezen(self) -> HezenValue {
+        HezenValue::Number(self.into())
+    }
+}
+
+impl FromHezen for f32 {
+    fn expected() -> String {
+        "a number".to_string()
+    }
+
+    fn from_hezen(value: &HezenValue) -> Option<Self> {
+        numeric::to_f64(value).map(|n| n as f32)
+    }
+}
+
+impl IntoHezen for String {
+    fn into_hezen(self) -> HezenValue {
+        HezenValue::String(self)
+    }
+}
+
+impl IntoHezen for &str {
+    fn into_hezen(self) -> HezenValue {
+        HezenValue::String(self.to_string())
+    }
+}
+
+impl FromHezen for String {
+    fn expected() -> String {
+        "a string".to_string()
+    }
+
+    fn from_hezen(value: &HezenValue) -> Option<Self> {
+        match value {
+            HezenValue::String(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl<T: IntoHezen> IntoHezen for Option<T> {
+    fn into_hezen(self) -> HezenValue {
+        match self {
+            Some(value) => value.into_hezen(),
+            None => HezenValue::Nil,
+        }
+    }
+}
+
+/// `nil` becomes `None`; anything else must convert to `T`.
+impl<T: FromHezen> FromHezen for Option<T> {
+    fn expected() -> String {
+        format!("{} or nil", T::expected())
+    }
+
+    fn from_hezen(value: &HezenValue) -> Option<Self> {
+        match value {
+            HezenValue::Nil => Some(None),
+            value => T::from_hezen(value).map(Some),
+        }
+    }
+}
+
+impl<T: IntoHezen> IntoHezen for Vec<T> {
+    fn into_hezen(self) -> HezenValue {
+        HezenValue::List(Rc::new(RefCell::new(
+            self.into_iter().map(IntoHezen::into_hezen).collect(),
+        )))
+    }
+}
+
+/// Accepts lists and tuples whose elements all convert to `T`.
+impl<T: FromHezen> FromHezen for Vec<T> {
+    fn expected() -> String {
+        format!("a list of elements that are each {}", T::expected())
+    }
+
+    fn from_hezen(value: &HezenValue) -> Option<Self> {
+        match value {
+            HezenValue::List(values) => values.borrow().iter().map(T::from_hezen).collect(),
+            HezenValue::Tuple(values) => values.iter().map(T::from_hezen).collect(),
+            _ => None,
+        }
+    }
+}
+
+/// Keys are sorted so that scripts see the same order on every run.
+impl<T: IntoHezen> IntoHezen for HashMap<String, T> {
+    fn into_hezen(self) -> HezenValue {
+        let mut entries: Vec<(String, T)> = self.into_iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        HezenValue::Map(Rc::new(RefCell::new(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key, value.into_hezen()))
+                .collect::<IndexMap<_, _>>(),
+        )))
+    }
+}
+
+impl<T: FromHezen> FromHezen for HashMap<String, T> {
+    fn expected() -> String {
+        format!("a map of values that are each {}", T::expected())
+    }
+
+    fn from_hezen(value: &HezenValue) -> Option<Self> {
+        match value {
+            HezenValue::Map(entries) => entries
+                .borrow()
+                .iter()
+                .map(|(key, value)| Some((key.clone(), T::from_hezen(value)?)))
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! tuple_conversions {
+    ($(($($name:ident: $index:tt),+)),*) => {
+        $(
+            impl<$($name: IntoHezen),+> IntoHezen for ($($name,)+) {
+                fn into_hezen(self) -> HezenValue {
+                    HezenValue::Tuple(Rc::new(vec![$(self.$index.into_hezen()),+]))
+                }
+            }
+
+            /// Accepts tuples and lists of exactly the right length.
+            impl<$($name: FromHezen),+> FromHezen for ($($name,)+) {
+                fn expected() -> String {
+                    let elements: Vec<String> = vec![$($name::expected()),+];
+                    format!("a tuple of {}", elements.join(", "))
+                }
+
+                fn from_hezen(value: &HezenValue) -> Option<Self> {
+                    let values = match value {
+                        HezenValue::Tuple(values) => values.to_vec(),
+                        HezenValue::List(values) => values.borrow().clone(),
+                        _ => return None,
+                    };
+
+                    if values.len() != [$($index),+].len() {
+                        return None;
+                    }
+
+                    Some(($($name::from_hezen(&values[$index])?,)+))
+                }
+            }
+        )*
+    };
+}
+
+tuple_conversions!(
+    (A: 0),
+    (A: 0, B: 1),
+    (A: 0, B: 1, C: 2),
+    (A: 0, B: 1, C: 2, D: 3)
+);
+
+/// What a function wrapped by [`native_fn!`](crate::native_fn) may return:
+/// a convertible value, or a `Result` whose error is raised in the script.
+pub trait NativeReturn {
+    fn into_result(self) -> Result<HezenValue, HezenError>;
+}
+
+impl<T: IntoHezen> NativeReturn for T {
+    fn into_result(self) -> Result<HezenValue, HezenError> {
+        Ok(self.into_hezen())
+    }
+}
+
+impl<T: IntoHezen> NativeReturn for Result<T, HezenError> {
+    fn into_result(self) -> Result<HezenValue, HezenError> {
+        self.map(IntoHezen::into_hezen)
+    }
+}
+
+/// Converts argument `index` of the native `function`, reporting a mismatch
+/// at `paren`. Used by [`native_fn!`](crate::native_fn).
+pub fn argument<T: FromHezen>(
+    paren: &Token,
+    function: &str,
+    index: usize,
+    value: &HezenValue,
+) -> Result<T, HezenError> {
+    T::from_hezen(value).ok_or_else(|| {
+        native_error(
+            paren,
+            format!(
+                "Expected argument {} of '{}' to be {}, not '{}'",
+                index + 1,
+                function,
+                T::expected(),
+                value.type_name()
+            ),
+        )
+    })
+}
+
+/// Wraps a Rust function as a [`HezenNativeFunction`](crate::HezenNativeFunction)
+/// named `$name`. The arity comes from the listed parameter types, and each
+/// argument is converted with [`FromHezen`] before the call:
+///
+/// ```
+/// use hezen_interpreter::{native_fn, Engine, HezenValue};
+///
+/// fn hypot(x: f64, y: f64) -> f64 {
+///     x.hypot(y)
+/// }
+///
+/// let mut engine = Engine::new();
+/// engine.define_native(native_fn!("hypot", hypot, f64, f64));
+///
+/// assert_eq!(engine.eval("hypot(3, 4);").unwrap(), HezenValue::Number(5.0));
+/// assert!(engine.eval("hypot(3, \"4\");").is_err());
+/// ```
+#[macro_export]
+macro_rules! native_fn {
+    ($name:literal, $function:expr $(, $ty:ty)* $(,)?) => {
+        $crate::HezenNativeFunction::builtin(
+            $name,
+            [$($crate::native_fn!(@unit $ty)),*].len(),
+            |paren, arguments| {
+                #[allow(unused_mut, unused_variables)]
+                let mut arguments = arguments.iter().enumerate();
+
+                $crate::NativeReturn::into_result($function($({
+                    let (index, value) = arguments.next().unwrap();
+                    $crate::convert_argument::<$ty>(paren, $name, index, value)?
+                }),*))
+            },
+        )
+    };
+    (@unit $ty:ty) => {
+        ()
+    };
+}
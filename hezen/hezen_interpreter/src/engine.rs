@@ -0,0 +1,431 @@
+use std::io::Write;
+
+use hezen_core::error::{HezenError, HezenErrorList, HezenLineInfo};
+
+use crate::{
+    cancellation::CancellationToken,
+    class::HezenClass,
+    convert::{FromHezen, IntoHezen},
+    environment::HezenValue,
+    function::HezenNativeFunction,
+    interpreter::Interpreter,
+    lexer::Lexer,
+    limits::Limits,
+    parser::Parser,
+    resolver::Resolver,
+    token::{Token, TokenType},
+};
+
+/// An embedded Hezen interpreter. Globals defined by one evaluation stay
+/// visible to the next, so a host can load a script once and then call into
+/// it.
+///
+/// ```
+/// use hezen_interpreter::{Engine, HezenValue};
+///
+/// let mut engine = Engine::new();
+/// engine.eval("fn double(x) { return x * 2; }").unwrap();
+///
+/// let result = engine.call("double", &[HezenValue::Integer(21)]).unwrap();
+/// assert_eq!(result, HezenValue::Integer(42));
+///
+/// engine.set_global("limit", HezenValue::Integer(10));
+/// engine.eval("var twice = double(limit);").unwrap();
+/// assert_eq!(engine.get_global("twice"), Some(HezenValue::Integer(20)));
+/// assert_eq!(engine.eval("twice + 1;").unwrap(), HezenValue::Integer(21));
+/// ```
+#[derive(Debug)]
+pub struct Engine {
+    interpreter: Interpreter,
+    warnings: HezenErrorList,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Self::with_interpreter(Interpreter::new())
+    }
+
+    /// Wraps an interpreter prepared by the host, such as one built with
+    /// [`InterpreterBuilder`](crate::InterpreterBuilder).
+    pub fn with_interpreter(interpreter: Interpreter) -> Self {
+        Self {
+            interpreter,
+            warnings: HezenErrorList::default(),
+        }
+    }
+
+    /// Evaluates `source`, reporting errors against the file name `<eval>`.
+    pub fn eval(&mut self, source: &str) -> Result<HezenValue, HezenErrorList> {
+        self.eval_named("<eval>", source)
+    }
+
+    /// Evaluates `source` as if read from `filename`. Returns the value of the
+    /// last statement when it is an expression, and `nil` otherwise.
+    pub fn eval_named(
+        &mut self,
+        filename: &str,
+        source: &str,
+    ) -> Result<HezenValue, HezenErrorList> {
+        let mut errors = HezenErrorList::default();
+
+        let tokens = Lexer::new(filename.to_string(), source.to_string(), &mut errors).get_tokens();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let ast = Parser::new(tokens, &mut errors).parse();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Resolver::new(&mut self.interpreter, &mut errors).resolve(&ast);
+
+        for warning in errors.take_warnings() {
+            self.warnings.add(warning);
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        self.interpreter
+            .interpret(&ast)
+            .map_err(HezenErrorList::from)
+    }
+
+    /// The code the script passed to `exit` during the last evaluation or
+    /// call, if it called it. Evaluation stops there without an error.
+    ///
+    /// ```
+    /// use hezen_interpreter::{Engine, HezenValue};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// assert_eq!(engine.eval("exit(3); 1;").unwrap(), HezenValue::Nil);
+    /// assert_eq!(engine.exit_code(), Some(3));
+    ///
+    /// engine.eval("1;").unwrap();
+    /// assert_eq!(engine.exit_code(), None);
+    /// ```
+    pub fn exit_code(&self) -> Option<i32> {
+        self.interpreter.exit_code()
+    }
+
+    /// Hands over the warnings collected by evaluations so far.
+    pub fn take_warnings(&mut self) -> HezenErrorList {
+        std::mem::take(&mut self.warnings)
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<HezenValue> {
+        self.interpreter.globals.get(&host_token(name)).ok()
+    }
+
+    /// Reads a global as a Rust value. Fails if the global is undefined or
+    /// does not convert to `T`.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use hezen_interpreter::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.set_global("names", vec!["ada", "grace"]);
+    /// engine
+    ///     .eval("var config = {\"retries\": 3, \"timeout\": nil}; var pair = (names[1], 2.5);")
+    ///     .unwrap();
+    ///
+    /// let config: HashMap<String, Option<i32>> = engine.get("config").unwrap();
+    /// assert_eq!(config["retries"], Some(3));
+    /// assert_eq!(config["timeout"], None);
+    ///
+    /// let pair: (String, f64) = engine.get("pair").unwrap();
+    /// assert_eq!(pair, ("grace".to_string(), 2.5));
+    ///
+    /// assert!(engine.get::<bool>("pair").is_err());
+    /// ```
+    pub fn get<T: FromHezen>(&self, name: &str) -> Result<T, HezenError> {
+        let value = self.get_global(name).ok_or_else(|| {
+            HezenError::runtime(
+                "<host>".to_string(),
+                0,
+                0,
+                format!("Undefined variable '{name}'"),
+            )
+        })?;
+
+        T::from_hezen(&value).ok_or_else(|| {
+            HezenError::runtime(
+                "<host>".to_string(),
+                0,
+                0,
+                format!(
+                    "Expected '{name}' to be {}, not '{}'",
+                    T::expected(),
+                    value.type_name()
+                ),
+            )
+        })
+    }
+
+    /// Defines or replaces a global. Scripts can read it but not assign to it.
+    pub fn set_global(&mut self, name: &str, value: impl IntoHezen) {
+        self.interpreter
+            .globals
+            .define(host_token(name), value.into_hezen(), false);
+    }
+
+    /// Calls the global function or class `name`.
+    pub fn call(
+        &mut self,
+        name: &str,
+        arguments: &[HezenValue],
+    ) -> Result<HezenValue, HezenErrorList> {
+        let callee = self.get_global(name).ok_or_else(|| {
+            HezenErrorList::from(HezenError::runtime(
+                "<host>".to_string(),
+                0,
+                0,
+                format!("Undefined variable '{name}'"),
+            ))
+        })?;
+
+        self.call_value(callee, arguments)
+    }
+
+    /// Calls any callable value, such as a function a script returned.
+    pub fn call_value(
+        &mut self,
+        callee: HezenValue,
+        arguments: &[HezenValue],
+    ) -> Result<HezenValue, HezenErrorList> {
+        self.interpreter.start_run();
+
+        let result = self
+            .interpreter
+            .call_value(callee, &host_token("call"), arguments);
+
+        self.interpreter
+            .finish_run(result)
+            .map_err(HezenErrorList::from)
+    }
+
+    /// Defines a global native function, such as one made with
+    /// [`native_fn!`](crate::native_fn).
+    pub fn define_native(&mut self, function: HezenNativeFunction) {
+        self.interpreter.define_native(function);
+    }
+
+    /// Defines a global native function backed by a Rust closure. The closure
+    /// receives the interpreter, so it can call back into Hezen values, and
+    /// the token of the call, for positioning its errors.
+    ///
+    /// ```
+    /// use std::{cell::Cell, rc::Rc};
+    ///
+    /// use hezen_interpreter::{Engine, HezenValue};
+    ///
+    /// let calls = Rc::new(Cell::new(0));
+    /// let mut engine = Engine::new();
+    ///
+    /// let counter = calls.clone();
+    /// engine.register("twice", 2, move |interpreter, paren, args| {
+    ///     counter.set(counter.get() + 1);
+    ///     let once = interpreter.call_value(args[0].clone(), paren, &args[1..])?;
+    ///     interpreter.call_value(args[0].clone(), paren, &[once])
+    /// });
+    ///
+    /// engine.eval("fn inc(x) { return x + 1; }").unwrap();
+    /// let result = engine.eval("twice(inc, 40);").unwrap();
+    ///
+    /// assert_eq!(result, HezenValue::Integer(42));
+    /// assert_eq!(calls.get(), 1);
+    /// ```
+    pub fn register(
+        &mut self,
+        name: &str,
+        arity: usize,
+        function: impl Fn(&mut Interpreter, &Token, &[HezenValue]) -> Result<HezenValue, HezenError>
+            + 'static,
+    ) {
+        self.interpreter.register(name, arity, function);
+    }
+
+    /// Defines a native class, built with [`HezenClass::native`].
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    ///
+    /// use hezen_interpreter::{Engine, HezenClass, HezenValue};
+    ///
+    /// let counter = HezenClass::native("Counter")
+    ///     .method("init", 1, |_, _, instance, args| {
+    ///         let start = match args[0] {
+    ///             HezenValue::Integer(start) => start,
+    ///             _ => 0,
+    ///         };
+    ///         instance.set_data(Cell::new(start));
+    ///         Ok(HezenValue::Nil)
+    ///     })
+    ///     .method("increment", 0, |_, _, instance, _| {
+    ///         let count = instance.data::<Cell<i64>>().unwrap();
+    ///         count.set(count.get() + 1);
+    ///         Ok(HezenValue::Integer(count.get()))
+    ///     });
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.register_class(counter);
+    /// engine
+    ///     .eval(
+    ///         "class Clicks < Counter {
+    ///              init(label) { super.init(10); self.label = label; }
+    ///          }
+    ///          var clicks = Clicks(\"button\");
+    ///          clicks.increment();",
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let result = engine.eval("clicks.increment();").unwrap();
+    /// assert_eq!(result, HezenValue::Integer(12));
+    /// ```
+    pub fn register_class(&mut self, class: HezenClass) {
+        self.interpreter.define_class(class);
+    }
+
+    /// Bounds every later evaluation and call. Each one gets the full
+    /// instruction budget, time limit and memory limit again.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use hezen_interpreter::{Engine, Limits};
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.set_limits(Limits {
+    ///     instructions: Some(10_000),
+    ///     time: Some(Duration::from_secs(5)),
+    ///     ..Limits::default()
+    /// });
+    ///
+    /// let errors = engine.eval("while true {}").unwrap_err();
+    /// let error = errors.iter().next().unwrap();
+    /// assert_eq!(error.kind(), "InstructionLimitError");
+    ///
+    /// engine.set_limits(Limits {
+    ///     time: Some(Duration::from_millis(20)),
+    ///     ..Limits::default()
+    /// });
+    ///
+    /// let errors = engine.eval("var mut i = 0; while true { i += 1; }").unwrap_err();
+    /// assert_eq!(errors.iter().next().unwrap().kind(), "TimeoutError");
+    /// ```
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.interpreter.set_limits(limits);
+    }
+
+    /// A token the host can cancel, from any thread, to stop the running
+    /// evaluation or call with an [`Interrupted`](HezenError::Interrupted)
+    /// error. Scripts cannot catch the interruption, and later evaluations
+    /// run normally.
+    ///
+    /// ```
+    /// use hezen_interpreter::{Engine, HezenValue};
+    ///
+    /// let mut engine = Engine::new();
+    /// let token = engine.cancellation_token();
+    ///
+    /// engine.register("stop", 0, move |_, _, _| {
+    ///     token.cancel();
+    ///     Ok(HezenValue::Nil)
+    /// });
+    ///
+    /// let errors = engine
+    ///     .eval("try { stop(); while true {} } catch err { println(err); }")
+    ///     .unwrap_err();
+    /// assert_eq!(errors.iter().next().unwrap().kind(), "Interrupted");
+    /// assert_eq!(errors.exit_code(), 130);
+    ///
+    /// assert_eq!(engine.eval("1 + 1;").unwrap(), HezenValue::Integer(2));
+    /// ```
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.interpreter.cancellation_token()
+    }
+
+    /// Roughly how many bytes the last evaluation or call allocated. Memory
+    /// is counted as it is allocated and not given back when values are
+    /// dropped, so this only grows during a run.
+    ///
+    /// ```
+    /// use hezen_interpreter::{Engine, Limits};
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.eval("var words = [\"a\", \"b\", \"c\"];").unwrap();
+    /// assert!(engine.memory_used() > 0);
+    ///
+    /// engine.set_limits(Limits {
+    ///     memory: Some(64 * 1024),
+    ///     ..Limits::default()
+    /// });
+    ///
+    /// let errors = engine
+    ///     .eval("var mut s = \"x\"; while true { s = s + s; }")
+    ///     .unwrap_err();
+    /// let error = errors.iter().next().unwrap();
+    /// assert_eq!(error.kind(), "MemoryError");
+    /// assert_eq!(error.message(), "Memory limit of 65536 bytes exceeded");
+    /// ```
+    pub fn memory_used(&self) -> usize {
+        self.interpreter.memory_used()
+    }
+
+    /// Where `print` and `println` write.
+    pub fn stdout(&mut self) -> &mut dyn Write {
+        self.interpreter.stdout()
+    }
+
+    /// Where the host reports warnings and errors.
+    pub fn stderr(&mut self) -> &mut dyn Write {
+        self.interpreter.stderr()
+    }
+
+    /// Redirects script output, which otherwise goes to the process's stdout.
+    ///
+    /// ```
+    /// use hezen_interpreter::{Engine, SharedBuffer};
+    ///
+    /// let output = SharedBuffer::new();
+    /// let mut engine = Engine::new();
+    /// engine.set_stdout(output.clone());
+    ///
+    /// engine.eval("print(\"a\"); println(1 + 1);").unwrap();
+    /// assert_eq!(output.contents(), "a2\n");
+    /// ```
+    pub fn set_stdout(&mut self, writer: impl Write + 'static) {
+        self.interpreter.set_stdout(writer);
+    }
+
+    pub fn set_stderr(&mut self, writer: impl Write + 'static) {
+        self.interpreter.set_stderr(writer);
+    }
+
+    /// The doc comment of a global function or class, or of a method when
+    /// given `Class.method`.
+    pub fn documentation(&self, path: &str) -> Option<String> {
+        self.interpreter.documentation(path)
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn host_token(name: &str) -> Token {
+    Token::new(
+        TokenType::Identifier,
+        name.to_string(),
+        HezenLineInfo::new("<host>".to_string(), 0, 0),
+    )
+}
@@ -0,0 +1,129 @@
+use std::{
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use hezen_core::error::HezenError;
+
+use crate::{
+    environment::HezenValue, function::HezenCallable, interpreter::Interpreter, token::Token,
+};
+
+#[derive(Debug, Clone)]
+pub struct HezenEnum {
+    pub name: String,
+    pub variants: HashMap<String, Rc<HezenEnumVariant>>,
+}
+
+impl HezenEnum {
+    pub fn new(name: String, variants: Vec<(String, Vec<String>)>) -> Rc<Self> {
+        Rc::new_cyclic(|enumeration| {
+            let variants = variants
+                .into_iter()
+                .map(|(variant, fields)| {
+                    (
+                        variant.clone(),
+                        Rc::new(HezenEnumVariant {
+                            enumeration: enumeration.clone(),
+                            enum_name: name.clone(),
+                            name: variant,
+                            fields,
+                        }),
+                    )
+                })
+                .collect();
+
+            Self { name, variants }
+        })
+    }
+
+    /// Looks up a variant by name. Variants without a payload are values on
+    /// their own, the others are constructors that have to be called.
+    pub fn get(&self, name: &str) -> Option<HezenValue> {
+        self.variants.get(name).map(|variant| {
+            if variant.fields.is_empty() {
+                HezenValue::EnumValue(Rc::new(HezenEnumValue {
+                    variant: variant.clone(),
+                    payload: Vec::new(),
+                }))
+            } else {
+                HezenValue::EnumVariant(variant.clone())
+            }
+        })
+    }
+}
+
+/// Enums are equal only to themselves, so that two enums declared with the
+/// same name are still told apart.
+impl PartialEq for HezenEnum {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HezenEnumVariant {
+    /// The enum that declared the variant.
+    pub enumeration: Weak<HezenEnum>,
+    pub enum_name: String,
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
+impl HezenEnumVariant {
+    pub fn belongs_to(&self, enumeration: &HezenEnum) -> bool {
+        std::ptr::eq(self.enumeration.as_ptr(), enumeration)
+    }
+}
+
+impl PartialEq for HezenEnumVariant {
+    fn eq(&self, other: &Self) -> bool {
+        Weak::ptr_eq(&self.enumeration, &other.enumeration) && self.name == other.name
+    }
+}
+
+impl HezenCallable for Rc<HezenEnumVariant> {
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        _paren: &Token,
+        arguments: &[HezenValue],
+    ) -> Result<HezenValue, HezenError> {
+        Ok(HezenValue::EnumValue(Rc::new(HezenEnumValue {
+            variant: self.clone(),
+            payload: arguments.to_vec(),
+        })))
+    }
+
+    fn arity(&self) -> usize {
+        self.fields.len()
+    }
+
+    fn name(&self) -> String {
+        format!("{}.{}", self.enum_name, self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HezenEnumValue {
+    pub variant: Rc<HezenEnumVariant>,
+    pub payload: Vec<HezenValue>,
+}
+
+impl HezenEnumValue {
+    pub fn is_variant(&self, enumeration: &HezenEnum, variant: &str) -> bool {
+        self.variant.belongs_to(enumeration) && self.variant.name == variant
+    }
+
+    pub fn get(&self, field: &str) -> Option<HezenValue> {
+        self.variant
+            .fields
+            .iter()
+            .position(|f| f == field)
+            .map(|index| self.payload[index].clone())
+    }
+
+    pub fn type_name(&self) -> String {
+        format!("{}.{}", self.variant.enum_name, self.variant.name)
+    }
+}
@@ -1,12 +1,19 @@
 use std::{cell::RefCell, collections::HashMap, fmt::Display, rc::Rc};
 
 use hezen_core::error::HezenError;
+use indexmap::IndexMap;
+use num_bigint::BigInt;
+use rust_decimal::Decimal;
 
 use crate::{
     ast::Literal,
     class::HezenClass,
+    enumeration::{HezenEnum, HezenEnumValue, HezenEnumVariant},
+    exception::HezenException,
     function::{HezenFunction, HezenNativeFunction},
     instance::HezenInstanceHandle,
+    module::HezenModule,
+    numeric,
     token::Token,
 };
 
@@ -44,10 +51,8 @@ impl HezenEnvironmentHandle {
                 var.value = value;
                 return Ok(());
             } else {
-                return Err(HezenError::runtime(
-                    name.position.file.clone(),
-                    name.position.line,
-                    name.position.column,
+                return Err(HezenError::Runtime(
+                    name.position.clone(),
                     format!("Cannot assign to immutable variable '{}'", name.lexeme),
                 ));
             }
@@ -57,10 +62,8 @@ impl HezenEnvironmentHandle {
             return enclosing.assign(name, value);
         }
 
-        Err(HezenError::runtime(
-            name.position.file.clone(),
-            name.position.line,
-            name.position.column,
+        Err(HezenError::Runtime(
+            name.position.clone(),
             format!("Undefined variable IN ENVIRONMENT ASSIGN '{}'", name.lexeme),
         ))
     }
@@ -74,10 +77,8 @@ impl HezenEnvironmentHandle {
             return enclosing.get(name);
         }
 
-        Err(HezenError::runtime(
-            name.position.file.clone(),
-            name.position.line,
-            name.position.column,
+        Err(HezenError::Runtime(
+            name.position.clone(),
             format!("Undefined variable IN ENVIRONMENT GET '{}'", name.lexeme),
         ))
     }
@@ -139,10 +140,14 @@ impl HezenEnvironmentHandle {
 
     pub(crate) fn all_values(&self) -> HashMap<String, (HezenVariable, usize)> {
         if let Some(enclosing) = &self.env.borrow().enclosing {
-            let mut values = enclosing.all_values().iter_mut().map(|(k, v)| {
-                v.1 += 1;
-                (k.clone(), v.clone())
-            }).collect::<HashMap<String, (HezenVariable, usize)>>();
+            let mut values = enclosing
+                .all_values()
+                .iter_mut()
+                .map(|(k, v)| {
+                    v.1 += 1;
+                    (k.clone(), v.clone())
+                })
+                .collect::<HashMap<String, (HezenVariable, usize)>>();
             for (key, value) in self.env.borrow().values.iter() {
                 values.insert(key.clone(), (value.clone(), 0));
             }
@@ -194,39 +199,113 @@ pub enum HezenValue {
     Nil,
     Bool(bool),
     Number(f64),
+    Integer(i64),
+    BigInt(BigInt),
+    Decimal(Decimal),
     String(String),
     Function(Rc<HezenFunction>),
     NativeFunction(Rc<HezenNativeFunction>),
     Class(Rc<HezenClass>),
     Instance(HezenInstanceHandle),
+    Enum(Rc<HezenEnum>),
+    EnumVariant(Rc<HezenEnumVariant>),
+    EnumValue(Rc<HezenEnumValue>),
+    Tuple(Rc<Vec<HezenValue>>),
+    List(Rc<RefCell<Vec<HezenValue>>>),
+    Map(Rc<RefCell<IndexMap<String, HezenValue>>>),
+    Module(Rc<HezenModule>),
+    Exception(Rc<HezenException>),
 }
 
 impl PartialEq for HezenValue {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Self::Nil, Self::Nil) => true,
-            (Self::Bool(a), Self::Bool(b)) => a == b,
-            (Self::Number(a), Self::Number(b)) => a == b,
-            (Self::String(a), Self::String(b)) => a == b,
-            (Self::Function(a), Self::Function(b)) => a == b,
-            (Self::Class(a), Self::Class(b)) => a == b,
-            (Self::Instance(a), Self::Instance(b)) => a == b,
-            _ => false,
+        values_equal(self, other, &mut Vec::new())
+    }
+}
+
+/// Compares two values. `comparing` holds the pairs of lists and maps being
+/// compared further up, which are taken to be equal when they come around
+/// again, so that values containing themselves can be compared.
+fn values_equal(a: &HezenValue, b: &HezenValue, comparing: &mut Vec<(usize, usize)>) -> bool {
+    match (a, b) {
+        (HezenValue::Nil, HezenValue::Nil) => true,
+        (HezenValue::Bool(a), HezenValue::Bool(b)) => a == b,
+        (a, b) if numeric::is_number(a) && numeric::is_number(b) => {
+            matches!(numeric::compare(a, b), Ok(Some(std::cmp::Ordering::Equal)))
         }
+        (HezenValue::String(a), HezenValue::String(b)) => a == b,
+        (HezenValue::Function(a), HezenValue::Function(b)) => a == b,
+        (HezenValue::Class(a), HezenValue::Class(b)) => a == b,
+        (HezenValue::Instance(a), HezenValue::Instance(b)) => a == b,
+        (HezenValue::Enum(a), HezenValue::Enum(b)) => a == b,
+        (HezenValue::EnumVariant(a), HezenValue::EnumVariant(b)) => a == b,
+        (HezenValue::EnumValue(a), HezenValue::EnumValue(b)) => {
+            a.variant == b.variant && all_equal(&a.payload, &b.payload, comparing)
+        }
+        (HezenValue::Tuple(a), HezenValue::Tuple(b)) => {
+            Rc::ptr_eq(a, b) || all_equal(a, b, comparing)
+        }
+        (HezenValue::List(a), HezenValue::List(b)) => {
+            let pair = (Rc::as_ptr(a) as usize, Rc::as_ptr(b) as usize);
+
+            if Rc::ptr_eq(a, b) || comparing.contains(&pair) {
+                return true;
+            }
+
+            comparing.push(pair);
+            let equal = all_equal(&a.borrow(), &b.borrow(), comparing);
+            comparing.pop();
+            equal
+        }
+        (HezenValue::Map(a), HezenValue::Map(b)) => {
+            let pair = (Rc::as_ptr(a) as usize, Rc::as_ptr(b) as usize);
+
+            if Rc::ptr_eq(a, b) || comparing.contains(&pair) {
+                return true;
+            }
+
+            comparing.push(pair);
+            let (a, b) = (a.borrow(), b.borrow());
+            let equal = a.len() == b.len()
+                && a.iter().all(|(key, value)| {
+                    b.get(key)
+                        .is_some_and(|other| values_equal(value, other, comparing))
+                });
+            comparing.pop();
+            equal
+        }
+        (HezenValue::Module(a), HezenValue::Module(b)) => a == b,
+        (HezenValue::Exception(a), HezenValue::Exception(b)) => a == b,
+        _ => false,
     }
 }
 
+fn all_equal(a: &[HezenValue], b: &[HezenValue], comparing: &mut Vec<(usize, usize)>) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| values_equal(a, b, comparing))
+}
+
 impl HezenValue {
     pub fn type_name(&self) -> String {
         match self {
             HezenValue::Nil => "nil".to_string(),
             HezenValue::Bool(_) => "bool".to_string(),
             HezenValue::Number(_) => "number".to_string(),
+            HezenValue::Integer(_) => "integer".to_string(),
+            HezenValue::BigInt(_) => "bigint".to_string(),
+            HezenValue::Decimal(_) => "decimal".to_string(),
             HezenValue::String(_) => "string".to_string(),
             HezenValue::Function(_) => "function".to_string(),
             HezenValue::Class(c) => format!("class {}", c.name),
             HezenValue::Instance(i) => format!("instance of {}", i.type_name()),
             HezenValue::NativeFunction(_) => "native function".to_string(),
+            HezenValue::Enum(e) => format!("enum {}", e.name),
+            HezenValue::EnumVariant(v) => format!("variant {}.{}", v.enum_name, v.name),
+            HezenValue::EnumValue(v) => v.type_name(),
+            HezenValue::Tuple(_) => "tuple".to_string(),
+            HezenValue::List(_) => "list".to_string(),
+            HezenValue::Map(_) => "map".to_string(),
+            HezenValue::Module(_) => "module".to_string(),
+            HezenValue::Exception(_) => "error".to_string(),
         }
     }
 
@@ -234,37 +313,138 @@ impl HezenValue {
         match self {
             HezenValue::Nil => false,
             HezenValue::Bool(b) => *b,
-            HezenValue::Number(n) => *n != 0.0,
+            HezenValue::Number(_)
+            | HezenValue::Integer(_)
+            | HezenValue::BigInt(_)
+            | HezenValue::Decimal(_) => !numeric::is_zero(self),
             HezenValue::String(s) => !s.is_empty(),
             HezenValue::Function(_) => true,
             HezenValue::Class(_) => true,
             HezenValue::Instance(_) => true,
             HezenValue::NativeFunction(_) => true,
+            HezenValue::Enum(_) => true,
+            HezenValue::EnumVariant(_) => true,
+            HezenValue::EnumValue(_) => true,
+            HezenValue::Tuple(t) => !t.is_empty(),
+            HezenValue::List(l) => !l.borrow().is_empty(),
+            HezenValue::Map(m) => !m.borrow().is_empty(),
+            HezenValue::Module(_) => true,
+            HezenValue::Exception(_) => true,
         }
     }
 }
 
 impl Display for HezenValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            HezenValue::Nil => write!(f, "nil"),
-            HezenValue::Bool(b) => write!(f, "{b}"),
-            HezenValue::Number(n) => write!(f, "{n}"),
-            HezenValue::String(s) => write!(f, "{s}"),
-            HezenValue::Function(hf) => write!(f, "<function {}>", hf.name.lexeme),
-            HezenValue::Class(hc) => write!(f, "<class {}>", hc.name),
-            HezenValue::Instance(hi) => write!(f, "<instance {}>", hi.type_name()),
-            HezenValue::NativeFunction(nf) => write!(f, "<native function {}>", nf.name.lexeme),
+        write_value(f, self, &mut Vec::new())
+    }
+}
+
+/// Writes a value. `showing` holds the lists and maps being written further
+/// up, which are shown as `[...]` and `{...}` when they contain themselves.
+fn write_value(
+    f: &mut std::fmt::Formatter<'_>,
+    value: &HezenValue,
+    showing: &mut Vec<usize>,
+) -> std::fmt::Result {
+    match value {
+        HezenValue::Nil => write!(f, "nil"),
+        HezenValue::Bool(b) => write!(f, "{b}"),
+        HezenValue::Number(n) => write!(f, "{n}"),
+        HezenValue::Integer(n) => write!(f, "{n}"),
+        HezenValue::BigInt(n) => write!(f, "{n}"),
+        HezenValue::Decimal(n) => write!(f, "{n}"),
+        HezenValue::String(s) => write!(f, "{s}"),
+        HezenValue::Function(hf) => write!(f, "<function {}>", hf.name.lexeme),
+        HezenValue::Class(hc) => write!(f, "<class {}>", hc.name),
+        HezenValue::Instance(hi) => write!(f, "<instance {}>", hi.type_name()),
+        HezenValue::NativeFunction(nf) => write!(f, "<native function {}>", nf.name.lexeme),
+        HezenValue::Enum(he) => write!(f, "<enum {}>", he.name),
+        HezenValue::EnumVariant(hv) => write!(f, "<variant {}.{}>", hv.enum_name, hv.name),
+        HezenValue::EnumValue(hv) => {
+            write!(f, "{}", hv.type_name())?;
+
+            if !hv.payload.is_empty() {
+                write!(f, "(")?;
+                write_values(f, &hv.payload, showing)?;
+                write!(f, ")")?;
+            }
+
+            Ok(())
         }
+        HezenValue::Tuple(values) => {
+            write!(f, "(")?;
+            write_values(f, values, showing)?;
+
+            if values.len() == 1 {
+                write!(f, ",")?;
+            }
+
+            write!(f, ")")
+        }
+        HezenValue::List(values) => {
+            let address = Rc::as_ptr(values) as usize;
+
+            if showing.contains(&address) {
+                return write!(f, "[...]");
+            }
+
+            showing.push(address);
+            write!(f, "[")?;
+            write_values(f, &values.borrow(), showing)?;
+            showing.pop();
+
+            write!(f, "]")
+        }
+        HezenValue::Map(entries) => {
+            let address = Rc::as_ptr(entries) as usize;
+
+            if showing.contains(&address) {
+                return write!(f, "{{...}}");
+            }
+
+            showing.push(address);
+            write!(f, "{{")?;
+            for (i, (key, value)) in entries.borrow().iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{key}: ")?;
+                write_value(f, value, showing)?;
+            }
+            showing.pop();
+
+            write!(f, "}}")
+        }
+        HezenValue::Module(module) => write!(f, "{module}"),
+        HezenValue::Exception(exception) => write!(f, "{exception}"),
     }
 }
 
+fn write_values(
+    f: &mut std::fmt::Formatter<'_>,
+    values: &[HezenValue],
+    showing: &mut Vec<usize>,
+) -> std::fmt::Result {
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write_value(f, value, showing)?;
+    }
+
+    Ok(())
+}
+
 impl From<&Literal> for HezenValue {
     fn from(literal: &Literal) -> Self {
         match literal {
             Literal::Nil => Self::Nil,
             Literal::Bool(b) => Self::Bool(*b),
             Literal::Number(n) => Self::Number(*n),
+            Literal::Integer(n) => Self::Integer(*n),
+            Literal::BigInt(n) => numeric::normalize_bigint(n.clone()),
+            Literal::Decimal(n) => Self::Decimal(*n),
             Literal::String(s) => Self::String(s.clone()),
         }
     }
@@ -0,0 +1,40 @@
+use std::fmt::Display;
+
+use hezen_core::error::{HezenError, HezenLineInfo};
+
+use crate::environment::HezenValue;
+
+/// A runtime error caught by a `try` statement, as seen by the script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HezenException {
+    pub kind: String,
+    pub message: String,
+    pub position: HezenLineInfo,
+}
+
+impl HezenException {
+    pub fn from_error(error: &HezenError) -> Self {
+        Self {
+            kind: error.kind().to_string(),
+            message: error.message().to_string(),
+            position: error.info().clone(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<HezenValue> {
+        match name {
+            "kind" => Some(HezenValue::String(self.kind.clone())),
+            "message" => Some(HezenValue::String(self.message.clone())),
+            "file" => Some(HezenValue::String(self.position.file.clone())),
+            "line" => Some(HezenValue::Integer(self.position.line as i64)),
+            "column" => Some(HezenValue::Integer(self.position.column as i64)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for HezenException {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.kind, self.message)
+    }
+}
@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use hezen_core::error::HezenError;
 
 use crate::{
@@ -5,13 +7,17 @@ use crate::{
     environment::{HezenEnvironmentHandle, HezenValue},
     instance::HezenInstanceHandle,
     interpreter::{HezenControl, HezenInterruption, Interpreter},
+    limits::{entry_size, ENVIRONMENT_SIZE},
     token::Token,
 };
 
 pub trait HezenCallable {
+    /// Calls the value for the call expression whose closing parenthesis is
+    /// `paren`, which errors are reported at.
     fn call(
         &self,
         interpreter: &mut Interpreter,
+        paren: &Token,
         arguments: &[HezenValue],
     ) -> Result<HezenValue, HezenError>;
     fn arity(&self) -> usize;
@@ -31,6 +37,7 @@ pub struct HezenFunction {
     pub name: Token,
     pub parameters: Vec<Token>,
     pub body: Stmt,
+    pub doc: Option<String>,
     closure: HezenEnvironmentHandle,
     initializer: bool,
 }
@@ -42,11 +49,13 @@ impl HezenFunction {
         body: Stmt,
         closure: HezenEnvironmentHandle,
         initializer: bool,
+        doc: Option<String>,
     ) -> Self {
         Self {
             name,
             parameters,
             body,
+            doc,
             closure,
             initializer,
         }
@@ -68,6 +77,7 @@ impl HezenFunction {
             name: self.name.clone(),
             parameters: self.parameters.clone(),
             body: self.body.clone(),
+            doc: self.doc.clone(),
             closure: environment,
             initializer: self.initializer,
         }
@@ -78,8 +88,20 @@ impl HezenCallable for HezenFunction {
     fn call(
         &self,
         interpreter: &mut Interpreter,
+        paren: &Token,
         arguments: &[HezenValue],
     ) -> Result<HezenValue, HezenError> {
+        // The scope of the parameters and the scope of the body.
+        interpreter.charge(
+            paren,
+            2 * ENVIRONMENT_SIZE
+                + self
+                    .parameters
+                    .iter()
+                    .map(|parameter| entry_size(&parameter.lexeme))
+                    .sum::<usize>(),
+        )?;
+
         let mut environment = HezenEnvironmentHandle::new(Some(self.closure.clone()));
 
         for (parameter, argument) in self.parameters.iter().zip(arguments) {
@@ -143,25 +165,73 @@ impl PartialEq for HezenFunction {
     }
 }
 
+/// The body of a native function: it gets the interpreter, the token of the
+/// call and the arguments.
+pub type NativeFn =
+    dyn Fn(&mut Interpreter, &Token, &[HezenValue]) -> Result<HezenValue, HezenError>;
+
 #[derive(Clone)]
 pub struct HezenNativeFunction {
     pub name: Token,
     pub arity: usize,
-    pub function: fn(&[HezenValue]) -> Result<HezenValue, HezenError>,
+    function: Rc<NativeFn>,
 }
 
 impl HezenNativeFunction {
     pub fn new(
         name: Token,
         arity: usize,
-        function: fn(&[HezenValue]) -> Result<HezenValue, HezenError>,
+        function: impl Fn(&mut Interpreter, &Token, &[HezenValue]) -> Result<HezenValue, HezenError>
+            + 'static,
     ) -> Self {
         Self {
             name,
             arity,
-            function,
+            function: Rc::new(function),
         }
     }
+
+    /// Creates a native function whose name token points at `<builtin>` and
+    /// that only looks at the token of the call and its arguments.
+    pub fn builtin(
+        name: &str,
+        arity: usize,
+        function: fn(&Token, &[HezenValue]) -> Result<HezenValue, HezenError>,
+    ) -> Self {
+        Self::with_interpreter(name, arity, move |_, paren, arguments| {
+            function(paren, arguments)
+        })
+    }
+
+    /// Creates a native function whose name token points at `<builtin>` and
+    /// that receives the interpreter and the token of the call.
+    pub fn with_interpreter(
+        name: &str,
+        arity: usize,
+        function: impl Fn(&mut Interpreter, &Token, &[HezenValue]) -> Result<HezenValue, HezenError>
+            + 'static,
+    ) -> Self {
+        Self::new(
+            Token::new(
+                crate::token::TokenType::Builtin,
+                name.to_string(),
+                hezen_core::error::HezenLineInfo::new("<builtin>".to_string(), 0, 0),
+            ),
+            arity,
+            function,
+        )
+    }
+
+    /// Calls the function for the call expression whose closing parenthesis
+    /// is `paren`.
+    pub fn call_at(
+        &self,
+        interpreter: &mut Interpreter,
+        paren: &Token,
+        arguments: &[HezenValue],
+    ) -> Result<HezenValue, HezenError> {
+        (self.function)(interpreter, paren, arguments)
+    }
 }
 
 impl std::fmt::Debug for HezenNativeFunction {
@@ -176,14 +246,13 @@ impl std::fmt::Debug for HezenNativeFunction {
 impl HezenCallable for HezenNativeFunction {
     fn call(
         &self,
-        _interpreter: &mut Interpreter,
+        interpreter: &mut Interpreter,
+        paren: &Token,
         arguments: &[HezenValue],
     ) -> Result<HezenValue, HezenError> {
         if arguments.len() != self.arity {
-            return Err(HezenError::runtime(
-                self.name.position.file.clone(),
-                self.name.position.line,
-                self.name.position.column,
+            return Err(HezenError::Runtime(
+                paren.position.clone(),
                 format!(
                     "Expected {} arguments but got {}.",
                     self.arity,
@@ -192,7 +261,7 @@ impl HezenCallable for HezenNativeFunction {
             ));
         }
 
-        (self.function)(arguments)
+        self.call_at(interpreter, paren, arguments)
     }
 
     fn arity(&self) -> usize {
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{any::Any, cell::RefCell, collections::HashMap, rc::Rc};
 
 use crate::{class::HezenClass, environment::HezenValue};
 
@@ -6,6 +6,8 @@ use crate::{class::HezenClass, environment::HezenValue};
 struct HezenInstance {
     pub class: Rc<HezenClass>,
     pub fields: HashMap<String, HezenValue>,
+    /// Rust data attached by a native class.
+    pub data: Option<Rc<dyn Any>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -13,9 +15,18 @@ pub struct HezenInstanceHandle {
     instance: Rc<RefCell<HezenInstance>>,
 }
 
+/// Instances are equal when their fields are. Rust data cannot be compared,
+/// so instances that have it are only equal to those sharing the same data.
 impl PartialEq for HezenInstance {
     fn eq(&self, other: &Self) -> bool {
+        let same_data = match (&self.data, &other.data) {
+            (None, None) => true,
+            (Some(data), Some(other)) => Rc::ptr_eq(data, other),
+            _ => false,
+        };
+
         self.class == other.class
+            && same_data
             && self
                 .fields
                 .iter()
@@ -29,6 +40,7 @@ impl HezenInstanceHandle {
             instance: Rc::new(RefCell::new(HezenInstance {
                 class,
                 fields: HashMap::default(),
+                data: None,
             })),
         }
     }
@@ -41,14 +53,52 @@ impl HezenInstanceHandle {
                 .borrow()
                 .class
                 .find_method(name)
-                .map(|m| HezenValue::Function(Rc::new(m.bind(self.clone()))))
+                .map(|m| m.bind(self.clone()))
         }
     }
 
+    pub(crate) fn has_field(&self, name: &str) -> bool {
+        self.instance.borrow().fields.contains_key(name)
+    }
+
     pub fn set(&self, name: String, value: HezenValue) {
         self.instance.borrow_mut().fields.insert(name, value);
     }
 
+    /// Attaches Rust data to the instance, replacing any previous data. Use
+    /// interior mutability for data that methods need to change.
+    pub fn set_data<T: 'static>(&self, data: T) {
+        self.instance.borrow_mut().data = Some(Rc::new(data));
+    }
+
+    /// The data attached with [`set_data`](Self::set_data), if it has type `T`.
+    pub fn data<T: 'static>(&self) -> Option<Rc<T>> {
+        let data = self.instance.borrow().data.clone()?;
+        data.downcast::<T>().ok()
+    }
+
+    /// The instance's fields, sorted by name.
+    pub fn fields(&self) -> Vec<(String, HezenValue)> {
+        let mut fields: Vec<(String, HezenValue)> = self
+            .instance
+            .borrow()
+            .fields
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        fields.sort_by(|a, b| a.0.cmp(&b.0));
+        fields
+    }
+
+    /// Identifies the instance, for telling apart instances with equal fields.
+    pub fn address(&self) -> usize {
+        Rc::as_ptr(&self.instance) as *const () as usize
+    }
+
+    pub fn is_instance_of(&self, class: &HezenClass) -> bool {
+        self.instance.borrow().class.is_subclass_of(class)
+    }
+
     pub fn type_name(&self) -> String {
         self.instance.borrow().class.name.clone()
     }
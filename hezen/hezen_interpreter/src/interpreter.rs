@@ -1,19 +1,39 @@
 use std::{
-    collections::HashMap,
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
     fmt::{Display, Formatter},
+    io::Write,
     rc::Rc,
 };
 
 use hezen_core::error::{HezenError, HezenLineInfo};
+use indexmap::IndexMap;
 
 use crate::{
-    ast::{Expr, Stmt},
+    ast::{Destructure, Expr, MatchArm, Pattern, Stmt},
+    cancellation::CancellationToken,
+    capabilities::{Capability, InterpreterBuilder},
     class::HezenClass,
+    enumeration::HezenEnum,
     environment::{HezenEnvironmentHandle, HezenValue},
+    exception::HezenException,
     function::{HezenCallable, HezenFunction, HezenNativeFunction},
+    io::{self, Output},
+    json,
+    limits::{
+        deep_size, entry_size, number_size, shallow_size, Allowance, Budget, Limits, INSTANCE_SIZE,
+    },
+    math,
+    module::native_error,
+    numeric::{self, ArithmeticOp, NumericError},
+    process, regex, string,
     token::{Token, TokenType},
 };
 
+const STACK_RED_ZONE: usize = 1024 * 1024;
+const STACK_GROWTH: usize = 16 * 1024 * 1024;
+
 #[derive(Debug)]
 pub(crate) enum HezenControl {
     Return(HezenValue),
@@ -42,231 +62,379 @@ pub struct Interpreter {
     pub globals: HezenEnvironmentHandle,
     environment: HezenEnvironmentHandle,
     pub locals: HashMap<Token, usize>,
+    stdout: Output,
+    stderr: Output,
+    budget: Budget,
+    /// The arguments `args` returns, given after `--` on the command line.
+    args: Vec<String>,
+    /// The code passed to `exit` during the current run.
+    exit_code: Option<i32>,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 macro_rules! binary_math_op {
-    ($left:ident, $right:ident, $operator:ident, $op:tt, $result:tt) => {
-        if let (HezenValue::Number(left), HezenValue::Number(right)) = ($left.clone(), $right.clone())
-        {
-            Ok(HezenValue::$result(left $op right))
-        } else {
-            Err(HezenError::runtime(
-                $operator.position.file.clone(),
-                $operator.position.line,
-                $operator.position.column,
-                format!(
-                    "Operands must be two numbers, not '{}' and '{}'",
-                    $left.type_name(),
-                    $right.type_name()
-                ),
-            ))
+    ($left:ident, $right:ident, $operator:ident, $op:expr) => {
+        numeric::arithmetic($op, &$left, &$right)
+            .map_err(|why| numeric_error($operator, why, &$left, &$right))
+    };
+}
+
+macro_rules! comparison_op {
+    ($left:ident, $right:ident, $operator:ident, $ordering:pat) => {
+        match (&$left, &$right) {
+            (HezenValue::String(l), HezenValue::String(r)) => Ok(Some(l.cmp(r))),
+            _ => numeric::compare(&$left, &$right),
         }
-    }
+        .map(|ordering| HezenValue::Bool(matches!(ordering, Some($ordering))))
+        .map_err(|why| numeric_error($operator, why, &$left, &$right))
+    };
+}
+
+fn numeric_error(
+    operator: &Token,
+    error: NumericError,
+    left: &HezenValue,
+    right: &HezenValue,
+) -> HezenError {
+    let message = match error {
+        NumericError::NotNumbers => format!(
+            "Operands must be two numbers, not '{}' and '{}'",
+            left.type_name(),
+            right.type_name()
+        ),
+        NumericError::DivisionByZero => "Division by zero".to_string(),
+        NumericError::Overflow => format!(
+            "Numeric overflow when applying '{}' to '{}' and '{}'",
+            operator.lexeme,
+            left.type_name(),
+            right.type_name()
+        ),
+    };
+
+    HezenError::Runtime(operator.position.clone(), message)
 }
 
 impl Interpreter {
+    /// An interpreter with every native available. Use
+    /// [`InterpreterBuilder`](crate::InterpreterBuilder) to leave some out.
     pub fn new() -> Self {
-        let mut globals = HezenEnvironmentHandle::default();
-
-        globals.define(
-            Token::new(
-                TokenType::Builtin,
-                "clock".to_string(),
-                HezenLineInfo {
-                    line: 0,
-                    column: 0,
-                    file: "<builtin>".to_string(),
-                },
-            ),
-            HezenValue::NativeFunction(Rc::new(HezenNativeFunction::new(
-                Token::new(
-                    TokenType::Builtin,
-                    "clock".to_string(),
-                    HezenLineInfo {
-                        line: 0,
-                        column: 0,
-                        file: "<builtin>".to_string(),
-                    },
-                ),
-                0,
-                |_| {
-                    Ok(HezenValue::Number(
-                        std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs_f64(),
+        InterpreterBuilder::new().allow_all().build()
+    }
+
+    pub(crate) fn with_capabilities(capabilities: &HashSet<Capability>) -> Self {
+        let globals = HezenEnvironmentHandle::default();
+
+        let mut interpreter = Self {
+            globals: globals.clone(),
+            environment: globals,
+            locals: HashMap::default(),
+            stdout: Output::new(std::io::stdout()),
+            stderr: Output::new(std::io::stderr()),
+            budget: Budget::default(),
+            args: Vec::new(),
+            exit_code: None,
+        };
+
+        if capabilities.contains(&Capability::Clock) {
+            interpreter.define_builtin("clock", 0, |_, _| {
+                Ok(HezenValue::Number(
+                    std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs_f64(),
+                ))
+            });
+        }
+
+        if capabilities.contains(&Capability::Print) {
+            interpreter.register("print", 1, |interpreter, paren, args| {
+                write!(interpreter.stdout(), "{}", args[0])
+                    .map_err(|why| io::io_error(paren, "Cannot write to stdout", why))?;
+                Ok(HezenValue::Nil)
+            });
+
+            interpreter.register("println", 1, |interpreter, paren, args| {
+                writeln!(interpreter.stdout(), "{}", args[0])
+                    .map_err(|why| io::io_error(paren, "Cannot write to stdout", why))?;
+                Ok(HezenValue::Nil)
+            });
+        }
+
+        interpreter.register("mod", 2, |_, paren, args| {
+            numeric::arithmetic(ArithmeticOp::Rem, &args[0], &args[1])
+                .map_err(|why| numeric_error(paren, why, &args[0], &args[1]))
+        });
+
+        interpreter.register("show", 1, |interpreter, paren, args| {
+            show(&args[0], interpreter.allowance(), paren).map(HezenValue::String)
+        });
+
+        interpreter.define_builtin("len", 1, |paren, args| {
+            let len = match &args[0] {
+                HezenValue::String(s) => s.chars().count(),
+                HezenValue::Tuple(t) => t.len(),
+                HezenValue::List(l) => l.borrow().len(),
+                HezenValue::Map(m) => m.borrow().len(),
+                other => {
+                    return Err(native_error(
+                        paren,
+                        format!("Cannot take the length of '{}'", other.type_name()),
                     ))
-                },
-            ))),
-            false,
-        );
+                }
+            };
 
-        globals.define(
-            Token::new(
-                TokenType::Builtin,
-                "print".to_string(),
-                HezenLineInfo {
-                    line: 0,
-                    column: 0,
-                    file: "<builtin>".to_string(),
-                },
-            ),
-            HezenValue::NativeFunction(Rc::new(HezenNativeFunction::new(
-                Token::new(
-                    TokenType::Builtin,
-                    "print".to_string(),
-                    HezenLineInfo {
-                        line: 0,
-                        column: 0,
-                        file: "<builtin>".to_string(),
-                    },
-                ),
-                1,
-                |args| {
-                    print!("{}", args[0]);
-                    Ok(HezenValue::Nil)
-                },
-            ))),
-            false,
-        );
+            Ok(HezenValue::Integer(len as i64))
+        });
 
-        globals.define(
-            Token::new(
-                TokenType::Builtin,
-                "println".to_string(),
-                HezenLineInfo {
-                    line: 0,
-                    column: 0,
-                    file: "<builtin>".to_string(),
-                },
-            ),
-            HezenValue::NativeFunction(Rc::new(HezenNativeFunction::new(
-                Token::new(
-                    TokenType::Builtin,
-                    "println".to_string(),
-                    HezenLineInfo {
-                        line: 0,
-                        column: 0,
-                        file: "<builtin>".to_string(),
-                    },
-                ),
-                1,
-                |args| {
-                    println!("{}", args[0]);
-                    Ok(HezenValue::Nil)
-                },
-            ))),
-            false,
-        );
+        interpreter.define_builtin("keys", 1, |paren, args| match &args[0] {
+            HezenValue::Map(map) => Ok(HezenValue::List(Rc::new(RefCell::new(
+                map.borrow()
+                    .keys()
+                    .cloned()
+                    .map(HezenValue::String)
+                    .collect(),
+            )))),
+            other => Err(native_error(
+                paren,
+                format!("Cannot take the keys of '{}'", other.type_name()),
+            )),
+        });
 
-        globals.define(
-            Token::new(
-                TokenType::Builtin,
-                "mod".to_string(),
-                HezenLineInfo {
-                    line: 0,
-                    column: 0,
-                    file: "<builtin>".to_string(),
-                },
-            ),
-            HezenValue::NativeFunction(Rc::new(HezenNativeFunction::new(
-                Token::new(
-                    TokenType::Builtin,
-                    "print".to_string(),
-                    HezenLineInfo {
-                        line: 0,
-                        column: 0,
-                        file: "<builtin>".to_string(),
-                    },
-                ),
-                2,
-                |args| {
-                    if let (HezenValue::Number(left), HezenValue::Number(right)) =
-                        (args[0].clone(), args[1].clone())
-                    {
-                        Ok(HezenValue::Number(left % right))
-                    } else {
-                        Err(HezenError::runtime(
-                            "<builtin>".to_string(),
-                            0,
-                            0,
-                            format!(
-                                "Operands must be two numbers, not '{}' and '{}'",
-                                args[0].type_name(),
-                                args[1].type_name()
-                            ),
-                        ))
-                    }
-                },
-            ))),
+        let natives = [
+            (Capability::Stdin, io::console_functions()),
+            (Capability::Filesystem, io::file_functions()),
+            (Capability::Environment, process::environment_functions()),
+            (Capability::Exit, process::exit_functions()),
+        ];
+
+        for (capability, functions) in natives {
+            if capabilities.contains(&capability) {
+                for function in functions {
+                    interpreter.define_native(function);
+                }
+            }
+        }
+
+        interpreter.define_class(regex::class());
+
+        for module in [math::module(), json::module()] {
+            interpreter.globals.define(
+                builtin_token(&module.name),
+                HezenValue::Module(Rc::new(module)),
+                false,
+            );
+        }
+
+        interpreter
+    }
+
+    /// Where `print` and `println` write. Defaults to the process's stdout.
+    pub fn stdout(&mut self) -> &mut dyn Write {
+        &mut self.stdout
+    }
+
+    /// Where the host reports warnings and errors. Defaults to the process's
+    /// stderr.
+    pub fn stderr(&mut self) -> &mut dyn Write {
+        &mut self.stderr
+    }
+
+    pub fn set_stdout(&mut self, writer: impl Write + 'static) {
+        self.stdout = Output::new(writer);
+    }
+
+    pub fn set_stderr(&mut self, writer: impl Write + 'static) {
+        self.stderr = Output::new(writer);
+    }
+
+    /// Makes `function` a global.
+    pub fn define_native(&mut self, function: HezenNativeFunction) {
+        self.globals.define(
+            function.name.clone(),
+            HezenValue::NativeFunction(Rc::new(function)),
             false,
         );
+    }
 
-        globals.define(
-            Token::new(
-                TokenType::Builtin,
-                "show".to_string(),
-                HezenLineInfo {
-                    line: 0,
-                    column: 0,
-                    file: "<builtin>".to_string(),
-                },
-            ),
-            HezenValue::NativeFunction(Rc::new(HezenNativeFunction::new(
-                Token::new(
-                    TokenType::Builtin,
-                    "show".to_string(),
-                    HezenLineInfo {
-                        line: 0,
-                        column: 0,
-                        file: "<builtin>".to_string(),
-                    },
-                ),
-                1,
-                |args| {
-                    Ok(match args[0] {
-                        HezenValue::Nil => HezenValue::String("nil".to_string()),
-                        _ => HezenValue::String(args[0].to_string()),
-                    })
-                },
-            ))),
+    /// Makes `class` a global, typically a native class built with
+    /// [`HezenClass::native`].
+    pub fn define_class(&mut self, class: HezenClass) {
+        self.globals.define(
+            builtin_token(&class.name),
+            HezenValue::Class(Rc::new(class)),
             false,
         );
+    }
 
-        Self {
-            globals: globals.clone(),
-            environment: globals,
-            locals: HashMap::default(),
-        }
+    /// Defines a global native function that needs nothing but the token of
+    /// the call, to report errors at, and its arguments.
+    pub fn define_builtin(
+        &mut self,
+        name: &str,
+        arity: usize,
+        function: fn(&Token, &[HezenValue]) -> Result<HezenValue, HezenError>,
+    ) {
+        self.define_native(HezenNativeFunction::builtin(name, arity, function));
+    }
+
+    /// Defines a global native function from a closure, which receives the
+    /// interpreter and the token of the call.
+    pub fn register(
+        &mut self,
+        name: &str,
+        arity: usize,
+        function: impl Fn(&mut Interpreter, &Token, &[HezenValue]) -> Result<HezenValue, HezenError>
+            + 'static,
+    ) {
+        self.define_native(HezenNativeFunction::new(
+            builtin_token(name),
+            arity,
+            function,
+        ));
     }
 
     pub fn resolve(&mut self, token: &Token, depth: usize) {
         self.locals.insert(token.clone(), depth);
     }
 
-    pub fn interpret(&mut self, statements: &[Stmt]) -> Result<(), HezenError> {
+    /// Runs the statements, returning the value of the last one if it is an
+    /// expression.
+    pub fn interpret(&mut self, statements: &[Stmt]) -> Result<HezenValue, HezenError> {
+        let mut value = HezenValue::Nil;
+
+        self.start_run();
+
         for statement in statements {
             let result = self.execute(statement);
 
-            if let Err(HezenInterruption::Error(error)) = result {
-                return Err(error);
+            match result {
+                Ok(result) if matches!(statement, Stmt::Expression(_)) => value = result,
+                Ok(_) => value = HezenValue::Nil,
+                Err(HezenInterruption::Error(error)) => return self.finish_run(Err(error)),
+                Err(HezenInterruption::Control(_)) => {
+                    panic!("Control flow should not be returned from the top level")
+                }
             }
+        }
 
-            if let Err(HezenInterruption::Control(_)) = result {
-                panic!("Control flow should not be returned from the top level");
-            }
+        Ok(value)
+    }
+
+    /// Looks up the doc comment of a global function or class, or of a method
+    /// when given `Class.method`.
+    pub fn documentation(&self, path: &str) -> Option<String> {
+        let (name, method) = match path.split_once('.') {
+            Some((name, method)) => (name, Some(method)),
+            None => (path, None),
+        };
+
+        let value = self
+            .globals
+            .get(&Token::new(
+                TokenType::Identifier,
+                name.to_string(),
+                HezenLineInfo::new("<help>".to_string(), 0, 0),
+            ))
+            .ok()?;
+
+        match (value, method) {
+            (HezenValue::Function(function), None) => function.doc.clone(),
+            (HezenValue::Class(class), None) => class.doc.clone(),
+            (HezenValue::Class(class), Some(method)) => class.find_method(method)?.doc(),
+            _ => None,
         }
+    }
+
+    pub fn limits(&self) -> &Limits {
+        &self.budget.limits
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.budget.limits = limits;
+    }
+
+    /// Restarts the instruction count, the clock and the memory count, as
+    /// happens at the start of every [`interpret`](Self::interpret).
+    pub fn start_run(&mut self) {
+        self.budget.start();
+        self.exit_code = None;
+    }
+
+    /// Turns the error that unwound a run ended by `exit` into a success,
+    /// leaving the code in [`exit_code`](Self::exit_code).
+    pub(crate) fn finish_run(
+        &self,
+        result: Result<HezenValue, HezenError>,
+    ) -> Result<HezenValue, HezenError> {
+        match (result, self.exit_code) {
+            (Err(_), Some(_)) => Ok(HezenValue::Nil),
+            (result, _) => result,
+        }
+    }
+
+    /// Ends the run with `code`. The returned error unwinds the script like
+    /// a cancellation, so `try` cannot catch it.
+    pub(crate) fn exit(&mut self, paren: &Token, code: i32) -> HezenError {
+        self.exit_code = Some(code);
+        HezenError::Interrupted(paren.position.clone())
+    }
+
+    /// The code the script passed to `exit` during the last run, if it
+    /// called it.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
 
-        Ok(())
+    /// The arguments the `args` native returns.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn set_args(&mut self, args: Vec<String>) {
+        self.args = args;
+    }
+
+    /// A token that stops the current run when cancelled. Every call returns
+    /// a handle to the same token.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.budget.cancellation.clone()
+    }
+
+    /// Roughly how many bytes the current run has allocated, as counted
+    /// against [`Limits::memory`].
+    pub fn memory_used(&self) -> usize {
+        self.budget.allocated
+    }
+
+    /// Counts `bytes` against the memory limit, reporting an exceeded limit
+    /// at `token`.
+    pub(crate) fn charge(&mut self, token: &Token, bytes: usize) -> Result<(), HezenError> {
+        self.budget.charge(bytes, token)
+    }
+
+    /// What natives may still allocate before the memory limit is exceeded.
+    pub(crate) fn allowance(&self) -> Allowance {
+        self.budget.allowance()
     }
 
     pub(crate) fn execute(&mut self, stmt: &Stmt) -> Result<HezenValue, HezenInterruption> {
+        // Statements without a token, such as blocks, cannot run for long on
+        // their own; loops step on every iteration below.
+        if let Some(token) = stmt.token() {
+            self.budget.step(token).map_err(HezenInterruption::Error)?;
+        }
+
         match stmt {
             Stmt::Block(stmts) => self.execute_block(
                 stmts.iter().collect(),
                 HezenEnvironmentHandle::new(Some(self.environment.clone())),
             ),
-            Stmt::Class(name, superclass, methods) => {
+            Stmt::Class(name, superclass, methods, doc) => {
                 let superclass = if let Some(superclass) = superclass {
                     match self
                         .evaluate(superclass)
@@ -274,10 +442,8 @@ impl Interpreter {
                     {
                         HezenValue::Class(superclass) => Some(superclass),
                         x => {
-                            return Err(HezenInterruption::Error(HezenError::runtime(
-                                name.position.file.clone(),
-                                name.position.line,
-                                name.position.column,
+                            return Err(HezenInterruption::Error(HezenError::Runtime(
+                                name.position.clone(),
                                 format!("Superclass must be a class, not '{}'", x.type_name()),
                             )))
                         }
@@ -288,19 +454,15 @@ impl Interpreter {
 
                 self.environment.define(name.clone(), HezenValue::Nil, true);
 
-                if superclass.is_some() {
+                if let Some(superclass) = &superclass {
                     self.environment = HezenEnvironmentHandle::new(Some(self.environment.clone()));
                     self.environment.define(
                         Token::new(
                             TokenType::Builtin,
                             "super".to_string(),
-                            HezenLineInfo {
-                                line: 0,
-                                column: 0,
-                                file: "<builtin>".to_string(),
-                            },
+                            HezenLineInfo::new("<builtin>".to_string(), 0, 0),
                         ),
-                        HezenValue::Class(superclass.as_ref().unwrap().clone()),
+                        HezenValue::Class(superclass.clone()),
                         false,
                     );
                 }
@@ -308,13 +470,14 @@ impl Interpreter {
                 let methods = methods
                     .iter()
                     .map(|method| {
-                        if let Stmt::Function(name, params, body) = method {
+                        if let Stmt::Function(name, params, body, doc) = method {
                             let function = Rc::new(HezenFunction::new(
                                 name.clone(),
                                 params.clone(),
                                 *body.clone(),
                                 self.environment.clone(),
                                 name.lexeme == "init",
+                                doc.clone(),
                             ));
 
                             (name.lexeme.clone(), function)
@@ -332,6 +495,7 @@ impl Interpreter {
                     name.lexeme.clone(),
                     superclass,
                     methods,
+                    doc.clone(),
                 )));
 
                 self.environment
@@ -340,14 +504,42 @@ impl Interpreter {
 
                 Ok(class)
             }
+            Stmt::Destructure(target, value, mutable) => {
+                let value = self.evaluate(value).map_err(HezenInterruption::Error)?;
+
+                self.destructure(target, value, *mutable)
+                    .map_err(HezenInterruption::Error)?;
+
+                Ok(HezenValue::Nil)
+            }
+            Stmt::Enum(name, variants) => {
+                let enumeration = HezenValue::Enum(HezenEnum::new(
+                    name.lexeme.clone(),
+                    variants
+                        .iter()
+                        .map(|(variant, fields)| {
+                            (
+                                variant.lexeme.clone(),
+                                fields.iter().map(|f| f.lexeme.clone()).collect(),
+                            )
+                        })
+                        .collect(),
+                ));
+
+                self.environment.define(name.clone(), enumeration, false);
+
+                Ok(HezenValue::Nil)
+            }
+            Stmt::Expression(Expr::Match(_, subject, arms)) => self.execute_match(subject, arms),
             Stmt::Expression(expr) => self.evaluate(expr).map_err(HezenInterruption::Error),
-            Stmt::Function(name, parameters, body) => {
+            Stmt::Function(name, parameters, body, doc) => {
                 let function = HezenValue::Function(Rc::new(HezenFunction::new(
                     name.clone(),
                     parameters.clone(),
                     *body.clone(),
                     self.environment.clone(),
                     false,
+                    doc.clone(),
                 )));
 
                 self.environment.define(name.clone(), function, false);
@@ -375,6 +567,8 @@ impl Interpreter {
                     HezenValue::Nil
                 };
 
+                self.charge(name, entry_size(&name.lexeme))
+                    .map_err(HezenInterruption::Error)?;
                 self.environment.define(name.clone(), value, false);
 
                 Ok(HezenValue::Nil)
@@ -387,16 +581,45 @@ impl Interpreter {
                     HezenValue::Nil
                 };
 
+                self.charge(name, entry_size(&name.lexeme))
+                    .map_err(HezenInterruption::Error)?;
                 self.environment.define(name.clone(), value, true);
 
                 Ok(HezenValue::Nil)
             }
-            Stmt::While(condition, body) => {
+            Stmt::Try(body, name, handler) => match self.execute(body) {
+                Err(HezenInterruption::Error(error)) if error.is_runtime() => {
+                    let mut environment =
+                        HezenEnvironmentHandle::new(Some(self.environment.clone()));
+
+                    if let Some(name) = name {
+                        environment.define(
+                            name.clone(),
+                            HezenValue::Exception(Rc::new(HezenException::from_error(&error))),
+                            false,
+                        );
+                    }
+
+                    match &**handler {
+                        Stmt::Block(statements) => {
+                            self.execute_block(statements.iter().collect(), environment)
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                result => result,
+            },
+            Stmt::While(keyword, condition, body) => {
                 while self
                     .evaluate(condition)
                     .map_err(HezenInterruption::Error)?
                     .is_truthy()
                 {
+                    self.budget
+                        .check_cancelled(keyword)
+                        .and_then(|_| self.budget.step(keyword))
+                        .map_err(HezenInterruption::Error)?;
+
                     let result = self.execute(body);
 
                     if let Err(HezenInterruption::Control(HezenControl::Break)) = result {
@@ -432,193 +655,62 @@ impl Interpreter {
     pub(crate) fn evaluate(&mut self, expr: &Expr) -> Result<HezenValue, HezenError> {
         match expr {
             Expr::Assign(name, value) => {
-                if self.locals.contains_key(name) {
-                    if self
-                        .environment
-                        .mutable_at(*self.locals.get(name).unwrap(), name)
-                    {
-                        let value = self.evaluate(value)?;
-                        self.environment
-                            .assign_at(*self.locals.get(name).unwrap(), name, value)?;
-                        Ok(HezenValue::Nil)
-                    } else {
-                        Err(HezenError::runtime(
-                            name.position.file.clone(),
-                            name.position.line,
-                            name.position.column,
-                            format!("Cannot assign to immutable variable '{}'", name.lexeme),
-                        ))
-                    }
-                } else if self.globals.mutable(name) {
-                    let value = self.evaluate(value)?;
-                    self.globals.assign(name, value)?;
-                    Ok(HezenValue::Nil)
-                } else {
-                    Err(HezenError::runtime(
-                        name.position.file.clone(),
-                        name.position.line,
-                        name.position.column,
-                        format!("Cannot assign to immutable variable '{}'", name.lexeme),
-                    ))
-                }
+                self.check_mutable(name)?;
+
+                let value = self.evaluate(value)?;
+                self.assign_variable(name, value)?;
+
+                Ok(HezenValue::Nil)
             }
             Expr::Binary(left, operator, right) => {
                 let left = self.evaluate(left)?;
                 let right = self.evaluate(right)?;
 
-                match operator.ty {
-                    TokenType::Plus => {
-                        if let (HezenValue::Number(left), HezenValue::Number(right)) = (left.clone(), right.clone())
-                        {
-                            Ok(HezenValue::Number(left + right))
-                        } else if let (HezenValue::String(left), HezenValue::String(right)) =
-                            (left.clone(), right.clone())
-                        {
-                            Ok(HezenValue::String(format!("{left}{right}")))
-                        } else {
-                            Err(HezenError::runtime(
-                                operator.position.file.clone(),
-                                operator.position.line,
-                                operator.position.column,
-                                format!(
-                                    "Operands must be two numbers or two strings, not '{}' and '{}'",
-                                    left.type_name(),
-                                    right.type_name()
-                                ),
-                            ))
-                        }
-                    }
-                    TokenType::Minus => {
-                        binary_math_op!(left, right, operator, -, Number)
-                    }
-                    TokenType::Star => {
-                        binary_math_op!(left, right, operator, *, Number)
-                    }
-                    TokenType::Slash => {
-                        binary_math_op!(left, right, operator, /, Number)
-                    }
-                    TokenType::Greater => {
-                        binary_math_op!(left, right, operator, >, Bool)
-                    }
-                    TokenType::GreaterEqual => {
-                        binary_math_op!(left, right, operator, >=, Bool)
-                    }
-                    TokenType::Less => {
-                        binary_math_op!(left, right, operator, <, Bool)
-                    }
-                    TokenType::LessEqual => {
-                        binary_math_op!(left, right, operator, <=, Bool)
-                    }
-                    TokenType::EqualEqual => {
-                        Ok(HezenValue::Bool(left == right))
-                    }
-                    TokenType::BangEqual => {
-                        Ok(HezenValue::Bool(left != right))
-                    }
-                    _ => Err(HezenError::runtime(
-                        operator.position.file.clone(),
-                        operator.position.line,
-                        operator.position.column,
-                        format!("Invalid binary operator '{}'. Don't know how you did it, but that's a parser bug", operator.lexeme),
-                    )),
+                self.binary_op(left, operator, right)
+            }
+            Expr::Call(..) | Expr::Get(..) | Expr::OptionalGet(..) => {
+                Ok(self.evaluate_chain(expr)?.unwrap_or(HezenValue::Nil))
+            }
+            Expr::Conditional(condition, then_expr, else_expr) => {
+                if self.evaluate(condition)?.is_truthy() {
+                    self.evaluate(then_expr)
+                } else {
+                    self.evaluate(else_expr)
                 }
             }
-            Expr::Call(callee, paren, args) => {
-                let callee = self.evaluate(callee)?;
+            Expr::CompoundAssign(..) | Expr::CompoundSet(..) | Expr::CompoundSetIndex(..) => {
+                self.compound_assign(expr).map(|(_, value)| value)
+            }
+            Expr::Grouping(expr) => self.evaluate(expr),
+            Expr::Postfix(assignment) => self.compound_assign(assignment).map(|(old, _)| old),
+            Expr::Index(object, bracket, index) => {
+                let object = self.evaluate(object)?;
+                let index = self.evaluate(index)?;
 
-                let mut arguments = Vec::new();
-                for arg in args {
-                    arguments.push(self.evaluate(arg)?);
+                self.get_index(&object, bracket, &index)
+            }
+            Expr::Interpolation(token, parts) => {
+                let mut text = self.allowance().text();
+                for part in parts {
+                    text.push(&self.evaluate(part)?, token)?;
                 }
 
-                match callee {
-                    HezenValue::Function(function) => {
-                        if arguments.len() != function.arity() {
-                            return Err(HezenError::runtime(
-                                paren.position.file.clone(),
-                                paren.position.line,
-                                paren.position.column,
-                                format!(
-                                    "Expected {} arguments but got {}",
-                                    function.arity(),
-                                    arguments.len()
-                                ),
-                            ));
-                        }
-
-                        function.call(self, &arguments)
-                    }
-                    HezenValue::NativeFunction(function) => {
-                        if arguments.len() != function.arity() {
-                            return Err(HezenError::runtime(
-                                paren.position.file.clone(),
-                                paren.position.line,
-                                paren.position.column,
-                                format!(
-                                    "Expected {} arguments but got {}",
-                                    function.arity(),
-                                    arguments.len()
-                                ),
-                            ));
-                        }
-
-                        function.call(self, &arguments)
-                    }
-                    HezenValue::Class(class) => {
-                        if arguments.len() != class.arity() {
-                            return Err(HezenError::runtime(
-                                paren.position.file.clone(),
-                                paren.position.line,
-                                paren.position.column,
-                                format!(
-                                    "Expected {} arguments but got {}",
-                                    class.arity(),
-                                    arguments.len()
-                                ),
-                            ));
-                        }
+                let result = HezenValue::String(text.into_string());
+                self.charge(token, shallow_size(&result))?;
 
-                        class.call(self, &arguments)
-                    }
-                    _ => Err(HezenError::runtime(
-                        paren.position.file.clone(),
-                        paren.position.line,
-                        paren.position.column,
-                        format!(
-                            "Can only call functions and classes, not '{}'",
-                            callee.type_name()
-                        ),
-                    )),
-                }
+                Ok(result)
             }
-            Expr::Get(expr, token) => {
-                let value = self.evaluate(expr)?;
-
-                match value {
-                    HezenValue::Instance(instance) => {
-                        if let Some(value) = instance.get(&token.lexeme) {
-                            Ok(value)
-                        } else {
-                            Err(HezenError::runtime(
-                                token.position.file.clone(),
-                                token.position.line,
-                                token.position.column,
-                                format!("Undefined property '{}'", token.lexeme),
-                            ))
-                        }
-                    }
-                    _ => Err(HezenError::runtime(
-                        token.position.file.clone(),
-                        token.position.line,
-                        token.position.column,
-                        format!(
-                            "Only instances have properties, '{}' does not",
-                            value.type_name()
-                        ),
-                    )),
+            Expr::List(bracket, elements) => {
+                let mut values = Vec::new();
+                for element in elements {
+                    values.push(self.evaluate(element)?);
                 }
+
+                let list = HezenValue::List(Rc::new(RefCell::new(values)));
+                self.charge(bracket, shallow_size(&list))?;
+
+                Ok(list)
             }
-            Expr::Grouping(expr) => self.evaluate(expr),
             Expr::Literal(l) => Ok(l.into()),
             Expr::Logical(left, op, right) => {
                 let left = self.evaluate(left)?;
@@ -638,14 +730,40 @@ impl Interpreter {
                             self.evaluate(right)
                         }
                     }
-                    _ => Err(HezenError::runtime(
-                        op.position.file.clone(),
-                        op.position.line,
-                        op.position.column,
+                    TokenType::QuestionQuestion => {
+                        if left == HezenValue::Nil {
+                            self.evaluate(right)
+                        } else {
+                            Ok(left)
+                        }
+                    }
+                    _ => Err(HezenError::Runtime(
+                        op.position.clone(),
                         format!("Invalid logical operator '{}'. Don't know how you did it, but that's a parser bug", op.lexeme),
                     )),
                 }
             }
+            Expr::Map(brace, entries) => {
+                let mut map = IndexMap::new();
+                for (key, value) in entries {
+                    let key = self.evaluate(key)?;
+                    let key = self.map_key(brace, &key)?;
+                    map.insert(key, self.evaluate(value)?);
+                }
+
+                let map = HezenValue::Map(Rc::new(RefCell::new(map)));
+                self.charge(brace, shallow_size(&map))?;
+
+                Ok(map)
+            }
+            Expr::Match(keyword, subject, arms) => match self.execute_match(subject, arms) {
+                Ok(value) => Ok(value),
+                Err(HezenInterruption::Error(error)) => Err(error),
+                Err(HezenInterruption::Control(control)) => Err(HezenError::Runtime(
+                    keyword.position.clone(),
+                    format!("Cannot {control} out of a match expression"),
+                )),
+            },
             Expr::Self_(token) => self.get(token, expr),
             Expr::Super(s, accessor) => {
                 let distance = self.locals.get(s).unwrap();
@@ -653,10 +771,8 @@ impl Interpreter {
                 let superclass = match self.environment.get_at(*distance, s) {
                     Ok(HezenValue::Class(class)) => class,
                     _ => {
-                        return Err(HezenError::runtime(
-                            s.position.file.clone(),
-                            s.position.line,
-                            s.position.column,
+                        return Err(HezenError::Runtime(
+                            s.position.clone(),
                             "Can only access superclass from a subclass".to_string(),
                         ))
                     }
@@ -668,10 +784,8 @@ impl Interpreter {
                 )? {
                     HezenValue::Instance(instance) => instance,
                     _ => {
-                        return Err(HezenError::runtime(
-                            s.position.file.clone(),
-                            s.position.line,
-                            s.position.column,
+                        return Err(HezenError::Runtime(
+                            s.position.clone(),
                             "Can only access superclass from a subclass".to_string(),
                         ))
                     }
@@ -680,11 +794,9 @@ impl Interpreter {
                 let method = superclass.find_method(&accessor.lexeme);
 
                 match method {
-                    Some(method) => Ok(HezenValue::Function(Rc::new(method.bind(object)))),
-                    None => Err(HezenError::runtime(
-                        accessor.position.file.clone(),
-                        accessor.position.line,
-                        accessor.position.column,
+                    Some(method) => Ok(method.bind(object)),
+                    None => Err(HezenError::Runtime(
+                        accessor.position.clone(),
                         format!("Undefined property '{}'", accessor.lexeme),
                     )),
                 }
@@ -696,36 +808,82 @@ impl Interpreter {
                     HezenValue::Instance(instance) => {
                         let value = self.evaluate(value)?;
 
+                        if !instance.has_field(&name.lexeme) {
+                            self.charge(name, entry_size(&name.lexeme))?;
+                        }
                         instance.set(name.lexeme.clone(), value.clone());
 
                         Ok(value)
                     }
-                    _ => Err(HezenError::runtime(
-                        name.position.file.clone(),
-                        name.position.line,
-                        name.position.column,
+                    _ => Err(HezenError::Runtime(
+                        name.position.clone(),
                         format!("Only instances have fields, '{}' does not", obj.type_name()),
                     )),
                 }
             }
+            Expr::SetIndex(object, bracket, index, value) => {
+                let object = self.evaluate(object)?;
+                let index = self.evaluate(index)?;
+
+                match &object {
+                    HezenValue::List(list) => {
+                        let len = list.borrow().len();
+                        let position = self.index_position(bracket, &index, len)?;
+                        let value = self.evaluate(value)?;
+
+                        list.borrow_mut()[position] = value.clone();
+
+                        Ok(value)
+                    }
+                    HezenValue::Map(map) => {
+                        let key = self.map_key(bracket, &index)?;
+                        let value = self.evaluate(value)?;
+
+                        if !map.borrow().contains_key(&key) {
+                            self.charge(bracket, entry_size(&key))?;
+                        }
+                        map.borrow_mut().insert(key, value.clone());
+
+                        Ok(value)
+                    }
+                    _ => Err(HezenError::Runtime(
+                        bracket.position.clone(),
+                        format!(
+                            "Only lists and maps support index assignment, not '{}'",
+                            object.type_name()
+                        ),
+                    )),
+                }
+            }
+            Expr::Tuple(paren, elements) => {
+                let mut values = Vec::new();
+                for element in elements {
+                    values.push(self.evaluate(element)?);
+                }
+
+                let tuple = HezenValue::Tuple(Rc::new(values));
+                self.charge(paren, shallow_size(&tuple))?;
+
+                Ok(tuple)
+            }
             Expr::Unary(op, right) => {
                 let right = self.evaluate(right)?;
 
                 match op.ty {
                     TokenType::Bang => Ok(HezenValue::Bool(!right.is_truthy())),
-                    TokenType::Minus => match right {
-                        HezenValue::Number(n) => Ok(HezenValue::Number(-n)),
-                        _ => Err(HezenError::runtime(
-                            op.position.file.clone(),
-                            op.position.line,
-                            op.position.column,
-                            format!("Operand must be a number, not '{}'", right.type_name()),
-                        )),
-                    },
-                    _ => Err(HezenError::runtime(
-                        op.position.file.clone(),
-                        op.position.line,
-                        op.position.column,
+                    TokenType::Minus => {
+                        let result = numeric::negate(&right).map_err(|_| {
+                            HezenError::Runtime(
+                                op.position.clone(),
+                                format!("Operand must be a number, not '{}'", right.type_name()),
+                            )
+                        })?;
+                        self.charge(op, number_size(&result))?;
+
+                        Ok(result)
+                    }
+                    _ => Err(HezenError::Runtime(
+                        op.position.clone(),
                         format!("Invalid unary operator '{}'. Don't know how you did it, but that's a parser bug", op.lexeme),
                     )),
                 }
@@ -761,6 +919,640 @@ impl Interpreter {
         Ok(value)
     }
 
+    /// Runs the first arm whose pattern matches `subject` and whose guard holds.
+    /// Evaluates to `nil` when no arm applies.
+    fn execute_match(
+        &mut self,
+        subject: &Expr,
+        arms: &[MatchArm],
+    ) -> Result<HezenValue, HezenInterruption> {
+        let value = self.evaluate(subject).map_err(HezenInterruption::Error)?;
+
+        for arm in arms {
+            let prev = self.environment.clone();
+
+            self.environment = HezenEnvironmentHandle::new(Some(prev.clone()));
+
+            let result = match self.match_pattern(&arm.pattern, &value) {
+                Ok(true) => match &arm.guard {
+                    Some(guard) => self.evaluate(guard).map(|g| g.is_truthy()),
+                    None => Ok(true),
+                },
+                other => other,
+            };
+
+            let result = match result {
+                Ok(true) => Some(self.execute(&arm.body)),
+                Ok(false) => None,
+                Err(error) => Some(Err(HezenInterruption::Error(error))),
+            };
+
+            self.environment = prev;
+
+            if let Some(result) = result {
+                return result;
+            }
+        }
+
+        Ok(HezenValue::Nil)
+    }
+
+    /// Checks `value` against `pattern`, defining any bindings in the current
+    /// environment along the way.
+    fn match_pattern(&mut self, pattern: &Pattern, value: &HezenValue) -> Result<bool, HezenError> {
+        match pattern {
+            Pattern::Wildcard(_) => Ok(true),
+            Pattern::Literal(_, literal) => Ok(HezenValue::from(literal) == *value),
+            Pattern::Range(_, start, end, inclusive) => {
+                if !numeric::is_number(value) {
+                    return Ok(false);
+                }
+
+                let above_start = matches!(
+                    numeric::compare(value, &start.into()),
+                    Ok(Some(Ordering::Greater | Ordering::Equal))
+                );
+                let below_end = match numeric::compare(value, &end.into()) {
+                    Ok(Some(Ordering::Less)) => true,
+                    Ok(Some(Ordering::Equal)) => *inclusive,
+                    _ => false,
+                };
+
+                Ok(above_start && below_end)
+            }
+            Pattern::Binding(name) => {
+                self.environment.define(name.clone(), value.clone(), false);
+                Ok(true)
+            }
+            Pattern::Instance(class_name, fields) => {
+                let class = match self.get(class_name, &Expr::Variable(class_name.clone()))? {
+                    HezenValue::Class(class) => class,
+                    other => {
+                        return Err(HezenError::Runtime(
+                            class_name.position.clone(),
+                            format!(
+                                "Instance patterns require a class, '{}' is a '{}'",
+                                class_name.lexeme,
+                                other.type_name()
+                            ),
+                        ))
+                    }
+                };
+
+                let instance = match value {
+                    HezenValue::Instance(instance) if instance.is_instance_of(&class) => instance,
+                    _ => return Ok(false),
+                };
+
+                for (field, pattern) in fields {
+                    let Some(field_value) = instance.get(&field.lexeme) else {
+                        return Ok(false);
+                    };
+
+                    let matched = match pattern {
+                        Some(pattern) => self.match_pattern(pattern, &field_value)?,
+                        None => {
+                            self.environment.define(field.clone(), field_value, false);
+                            true
+                        }
+                    };
+
+                    if !matched {
+                        return Ok(false);
+                    }
+                }
+
+                Ok(true)
+            }
+            Pattern::Variant(enum_name, variant, payload) => {
+                let enumeration = match self.get(enum_name, &Expr::Variable(enum_name.clone()))? {
+                    HezenValue::Enum(enumeration) => enumeration,
+                    other => {
+                        return Err(HezenError::Runtime(
+                            enum_name.position.clone(),
+                            format!(
+                                "Variant patterns require an enum, '{}' is a '{}'",
+                                enum_name.lexeme,
+                                other.type_name()
+                            ),
+                        ))
+                    }
+                };
+
+                if !enumeration.variants.contains_key(&variant.lexeme) {
+                    return Err(HezenError::Runtime(
+                        variant.position.clone(),
+                        format!(
+                            "Enum '{}' has no variant '{}'",
+                            enumeration.name, variant.lexeme
+                        ),
+                    ));
+                }
+
+                let enum_value = match value {
+                    HezenValue::EnumValue(v) if v.is_variant(&enumeration, &variant.lexeme) => v,
+                    _ => return Ok(false),
+                };
+
+                let Some(payload) = payload else {
+                    return Ok(true);
+                };
+
+                if payload.len() != enum_value.payload.len() {
+                    return Err(HezenError::Runtime(
+                        variant.position.clone(),
+                        format!(
+                            "Variant '{}' has {} fields but the pattern has {}",
+                            enum_value.type_name(),
+                            enum_value.payload.len(),
+                            payload.len()
+                        ),
+                    ));
+                }
+
+                for (pattern, field_value) in payload.iter().zip(enum_value.payload.iter()) {
+                    if !self.match_pattern(pattern, field_value)? {
+                        return Ok(false);
+                    }
+                }
+
+                Ok(true)
+            }
+            Pattern::Alternatives(patterns) => {
+                for pattern in patterns {
+                    if self.match_pattern(pattern, value)? {
+                        return Ok(true);
+                    }
+                }
+
+                Ok(false)
+            }
+        }
+    }
+
+    /// Evaluates a chain of calls and property accesses. Returns `None` when an
+    /// optional access (`?.`) hit `nil`, which short-circuits the rest of the chain.
+    fn evaluate_chain(&mut self, expr: &Expr) -> Result<Option<HezenValue>, HezenError> {
+        match expr {
+            Expr::Call(callee, paren, args) => {
+                let Some(callee) = self.evaluate_chain(callee)? else {
+                    return Ok(None);
+                };
+
+                let mut arguments = Vec::new();
+                for arg in args {
+                    arguments.push(self.evaluate(arg)?);
+                }
+
+                self.call_value(callee, paren, &arguments).map(Some)
+            }
+            Expr::Get(object, name) => {
+                let Some(value) = self.evaluate_chain(object)? else {
+                    return Ok(None);
+                };
+
+                self.get_property(value, name).map(Some)
+            }
+            Expr::OptionalGet(object, name) => match self.evaluate_chain(object)? {
+                None | Some(HezenValue::Nil) => Ok(None),
+                Some(value) => self.get_property(value, name).map(Some),
+            },
+            _ => self.evaluate(expr).map(Some),
+        }
+    }
+
+    /// Calls any callable value. `paren` is where errors about the call are
+    /// reported.
+    pub fn call_value(
+        &mut self,
+        callee: HezenValue,
+        paren: &Token,
+        arguments: &[HezenValue],
+    ) -> Result<HezenValue, HezenError> {
+        self.budget.enter(paren)?;
+
+        // Every Hezen call recurses through several large frames, so grow the
+        // stack on the heap instead of overflowing it before the call depth
+        // limit is reached.
+        let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_GROWTH, || {
+            self.invoke(callee, paren, arguments)
+        });
+
+        self.budget.exit();
+
+        result
+    }
+
+    fn invoke(
+        &mut self,
+        callee: HezenValue,
+        paren: &Token,
+        arguments: &[HezenValue],
+    ) -> Result<HezenValue, HezenError> {
+        match callee {
+            HezenValue::Function(function) => {
+                if arguments.len() != function.arity() {
+                    return Err(HezenError::Runtime(
+                        paren.position.clone(),
+                        format!(
+                            "Expected {} arguments but got {}",
+                            function.arity(),
+                            arguments.len()
+                        ),
+                    ));
+                }
+
+                function.call(self, paren, arguments)
+            }
+            HezenValue::NativeFunction(function) => {
+                if arguments.len() != function.arity() {
+                    return Err(HezenError::Runtime(
+                        paren.position.clone(),
+                        format!(
+                            "Expected {} arguments but got {}",
+                            function.arity(),
+                            arguments.len()
+                        ),
+                    ));
+                }
+
+                let result = function.call_at(self, paren, arguments)?;
+                self.charge(paren, deep_size(&result))?;
+
+                Ok(result)
+            }
+            HezenValue::Class(class) => {
+                if arguments.len() != class.arity() {
+                    return Err(HezenError::Runtime(
+                        paren.position.clone(),
+                        format!(
+                            "Expected {} arguments but got {}",
+                            class.arity(),
+                            arguments.len()
+                        ),
+                    ));
+                }
+
+                self.charge(paren, INSTANCE_SIZE)?;
+                class.call(self, paren, arguments)
+            }
+            HezenValue::EnumVariant(variant) => {
+                if arguments.len() != variant.arity() {
+                    return Err(HezenError::Runtime(
+                        paren.position.clone(),
+                        format!(
+                            "Expected {} arguments but got {}",
+                            variant.arity(),
+                            arguments.len()
+                        ),
+                    ));
+                }
+
+                variant.call(self, paren, arguments)
+            }
+            _ => Err(HezenError::Runtime(
+                paren.position.clone(),
+                format!(
+                    "Can only call functions and classes, not '{}'",
+                    callee.type_name()
+                ),
+            )),
+        }
+    }
+
+    fn get_property(&self, value: HezenValue, token: &Token) -> Result<HezenValue, HezenError> {
+        let property = match &value {
+            HezenValue::Instance(instance) => instance.get(&token.lexeme),
+            HezenValue::Enum(enumeration) => enumeration.get(&token.lexeme),
+            HezenValue::EnumValue(enum_value) => enum_value.get(&token.lexeme),
+            HezenValue::Module(module) => module.get(&token.lexeme),
+            HezenValue::Exception(exception) => exception.get(&token.lexeme),
+            HezenValue::String(s) => string::method(s, &token.lexeme)
+                .map(|method| HezenValue::NativeFunction(Rc::new(method))),
+            _ => {
+                return Err(HezenError::Runtime(
+                    token.position.clone(),
+                    format!(
+                        "Only instances have properties, '{}' does not",
+                        value.type_name()
+                    ),
+                ))
+            }
+        };
+
+        property.ok_or_else(|| {
+            HezenError::Runtime(
+                token.position.clone(),
+                format!("Undefined property '{}'", token.lexeme),
+            )
+        })
+    }
+
+    /// Defines the variables named by `target`, taking `value` apart as needed.
+    fn destructure(
+        &mut self,
+        target: &Destructure,
+        value: HezenValue,
+        mutable: bool,
+    ) -> Result<(), HezenError> {
+        match target {
+            Destructure::Name(name) => {
+                self.environment.define(name.clone(), value, mutable);
+                Ok(())
+            }
+            Destructure::Sequence(open, targets, rest) => {
+                let values = match &value {
+                    HezenValue::Tuple(values) => values.to_vec(),
+                    HezenValue::List(values) => values.borrow().clone(),
+                    _ => {
+                        return Err(HezenError::Runtime(
+                            open.position.clone(),
+                            format!("Cannot destructure '{}'", value.type_name()),
+                        ))
+                    }
+                };
+
+                if values.len() < targets.len() || (rest.is_none() && values.len() > targets.len())
+                {
+                    return Err(HezenError::Runtime(
+                        open.position.clone(),
+                        format!(
+                            "Expected {}{} values to destructure but got {}",
+                            if rest.is_some() { "at least " } else { "" },
+                            targets.len(),
+                            values.len()
+                        ),
+                    ));
+                }
+
+                let mut values = values.into_iter();
+
+                for target in targets {
+                    self.destructure(target, values.next().unwrap(), mutable)?;
+                }
+
+                if let Some(rest) = rest {
+                    self.environment.define(
+                        rest.clone(),
+                        HezenValue::List(Rc::new(RefCell::new(values.collect()))),
+                        mutable,
+                    );
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    fn get_index(
+        &self,
+        object: &HezenValue,
+        bracket: &Token,
+        index: &HezenValue,
+    ) -> Result<HezenValue, HezenError> {
+        match object {
+            HezenValue::List(list) => {
+                let list = list.borrow();
+                let position = self.index_position(bracket, index, list.len())?;
+                Ok(list[position].clone())
+            }
+            HezenValue::Tuple(tuple) => {
+                let position = self.index_position(bracket, index, tuple.len())?;
+                Ok(tuple[position].clone())
+            }
+            HezenValue::Map(map) => {
+                let key = self.map_key(bracket, index)?;
+                Ok(map.borrow().get(&key).cloned().unwrap_or(HezenValue::Nil))
+            }
+            HezenValue::String(s) => {
+                let position = self.index_position(bracket, index, s.chars().count())?;
+                Ok(HezenValue::String(
+                    s.chars().nth(position).unwrap().to_string(),
+                ))
+            }
+            _ => Err(HezenError::Runtime(
+                bracket.position.clone(),
+                format!("Cannot index into '{}'", object.type_name()),
+            )),
+        }
+    }
+
+    fn map_key(&self, token: &Token, key: &HezenValue) -> Result<String, HezenError> {
+        match key {
+            HezenValue::String(key) => Ok(key.clone()),
+            _ => Err(HezenError::Runtime(
+                token.position.clone(),
+                format!("Map keys must be strings, not '{}'", key.type_name()),
+            )),
+        }
+    }
+
+    /// Turns an index value into a position within a sequence of `len` items.
+    /// Negative indices count from the end.
+    fn index_position(
+        &self,
+        bracket: &Token,
+        index: &HezenValue,
+        len: usize,
+    ) -> Result<usize, HezenError> {
+        let HezenValue::Integer(i) = index else {
+            return Err(HezenError::Runtime(
+                bracket.position.clone(),
+                format!("Index must be an integer, not '{}'", index.type_name()),
+            ));
+        };
+
+        let position = if *i < 0 { len as i64 + i } else { *i };
+
+        if position < 0 || position >= len as i64 {
+            return Err(HezenError::Runtime(
+                bracket.position.clone(),
+                format!("Index {i} out of range for length {len}"),
+            ));
+        }
+
+        Ok(position as usize)
+    }
+
+    fn check_mutable(&self, name: &Token) -> Result<(), HezenError> {
+        let mutable = if let Some(distance) = self.locals.get(name) {
+            self.environment.mutable_at(*distance, name)
+        } else {
+            self.globals.mutable(name)
+        };
+
+        if mutable {
+            Ok(())
+        } else {
+            Err(HezenError::Runtime(
+                name.position.clone(),
+                format!("Cannot assign to immutable variable '{}'", name.lexeme),
+            ))
+        }
+    }
+
+    fn assign_variable(&mut self, name: &Token, value: HezenValue) -> Result<(), HezenError> {
+        if let Some(distance) = self.locals.get(name) {
+            self.environment.assign_at(*distance, name, value)
+        } else {
+            self.globals.assign(name, value)
+        }
+    }
+
+    /// Evaluates a compound assignment, returning the value before it and
+    /// the value it assigned.
+    fn compound_assign(&mut self, expr: &Expr) -> Result<(HezenValue, HezenValue), HezenError> {
+        match expr {
+            Expr::CompoundAssign(name, operator, value) => {
+                self.check_mutable(name)?;
+
+                let current = self.get(name, expr)?;
+                let value = self.evaluate(value)?;
+                let value = self.binary_op(current.clone(), operator, value)?;
+                self.assign_variable(name, value.clone())?;
+
+                Ok((current, value))
+            }
+            Expr::CompoundSet(obj, name, operator, value) => {
+                let obj = self.evaluate(obj)?;
+
+                match obj {
+                    HezenValue::Instance(instance) => {
+                        let current = instance.get(&name.lexeme).ok_or_else(|| {
+                            HezenError::Runtime(
+                                name.position.clone(),
+                                format!("Undefined property '{}'", name.lexeme),
+                            )
+                        })?;
+                        let value = self.evaluate(value)?;
+                        let value = self.binary_op(current.clone(), operator, value)?;
+
+                        instance.set(name.lexeme.clone(), value.clone());
+
+                        Ok((current, value))
+                    }
+                    _ => Err(HezenError::Runtime(
+                        name.position.clone(),
+                        format!("Only instances have fields, '{}' does not", obj.type_name()),
+                    )),
+                }
+            }
+            Expr::CompoundSetIndex(object, bracket, index, operator, value) => {
+                let object = self.evaluate(object)?;
+                let index = self.evaluate(index)?;
+
+                match &object {
+                    HezenValue::List(list) => {
+                        let len = list.borrow().len();
+                        let position = self.index_position(bracket, &index, len)?;
+                        let current = list.borrow()[position].clone();
+                        let value = self.evaluate(value)?;
+                        let value = self.binary_op(current.clone(), operator, value)?;
+
+                        list.borrow_mut()[position] = value.clone();
+
+                        Ok((current, value))
+                    }
+                    HezenValue::Map(map) => {
+                        let key = self.map_key(bracket, &index)?;
+                        let current = map.borrow().get(&key).cloned().ok_or_else(|| {
+                            HezenError::Runtime(
+                                bracket.position.clone(),
+                                format!("Undefined key '{key}'"),
+                            )
+                        })?;
+                        let value = self.evaluate(value)?;
+                        let value = self.binary_op(current.clone(), operator, value)?;
+
+                        map.borrow_mut().insert(key, value.clone());
+
+                        Ok((current, value))
+                    }
+                    _ => Err(HezenError::Runtime(
+                        bracket.position.clone(),
+                        format!(
+                            "Only lists and maps support index assignment, not '{}'",
+                            object.type_name()
+                        ),
+                    )),
+                }
+            }
+            _ => unreachable!("only compound assignments are evaluated here"),
+        }
+    }
+
+    fn binary_op(
+        &mut self,
+        left: HezenValue,
+        operator: &Token,
+        right: HezenValue,
+    ) -> Result<HezenValue, HezenError> {
+        let result = match operator.ty {
+            TokenType::Plus | TokenType::PlusEqual | TokenType::PlusPlus => {
+                if numeric::is_number(&left) && numeric::is_number(&right) {
+                    binary_math_op!(left, right, operator, ArithmeticOp::Add)
+                } else if let (HezenValue::String(left), HezenValue::String(right)) =
+                    (left.clone(), right.clone())
+                {
+                    self.charge(operator, size_of::<HezenValue>() + left.len() + right.len())?;
+                    Ok(HezenValue::String(format!("{left}{right}")))
+                } else {
+                    Err(HezenError::Runtime(
+                        operator.position.clone(),
+                        format!(
+                            "Operands must be two numbers or two strings, not '{}' and '{}'",
+                            left.type_name(),
+                            right.type_name()
+                        ),
+                    ))
+                }
+            }
+            TokenType::Minus | TokenType::MinusEqual | TokenType::MinusMinus => {
+                binary_math_op!(left, right, operator, ArithmeticOp::Sub)
+            }
+            TokenType::Star | TokenType::StarEqual => {
+                binary_math_op!(left, right, operator, ArithmeticOp::Mul)
+            }
+            TokenType::Slash | TokenType::SlashEqual => {
+                binary_math_op!(left, right, operator, ArithmeticOp::Div)
+            }
+            TokenType::Percent | TokenType::PercentEqual => {
+                binary_math_op!(left, right, operator, ArithmeticOp::Rem)
+            }
+            TokenType::StarStar => {
+                binary_math_op!(left, right, operator, ArithmeticOp::Pow)
+            }
+            TokenType::Greater => {
+                comparison_op!(left, right, operator, Ordering::Greater)
+            }
+            TokenType::GreaterEqual => {
+                comparison_op!(left, right, operator, Ordering::Greater | Ordering::Equal)
+            }
+            TokenType::Less => {
+                comparison_op!(left, right, operator, Ordering::Less)
+            }
+            TokenType::LessEqual => {
+                comparison_op!(left, right, operator, Ordering::Less | Ordering::Equal)
+            }
+            TokenType::EqualEqual => {
+                Ok(HezenValue::Bool(left == right))
+            }
+            TokenType::BangEqual => {
+                Ok(HezenValue::Bool(left != right))
+            }
+            _ => Err(HezenError::Runtime(
+                operator.position.clone(),
+                format!("Invalid binary operator '{}'. Don't know how you did it, but that's a parser bug", operator.lexeme),
+            )),
+        }?;
+
+        // Numbers are created here rather than by an expression that charges
+        // them, so their digits are charged for now.
+        self.charge(operator, number_size(&result))?;
+
+        Ok(result)
+    }
+
     fn get(&self, name: &Token, _expr: &Expr) -> Result<HezenValue, HezenError> {
         if let Some(distance) = self.locals.get(name) {
             self.environment.get_at(*distance, name)
@@ -769,3 +1561,21 @@ impl Interpreter {
         }
     }
 }
+
+/// Converts a value to the text `show` and string interpolation produce,
+/// failing at `at` once the text outgrows `allowance`.
+fn show(value: &HezenValue, allowance: Allowance, at: &Token) -> Result<String, HezenError> {
+    let mut text = allowance.text();
+    text.push(value, at)?;
+
+    Ok(text.into_string())
+}
+
+/// Names a builtin function, module or class that has no source position.
+fn builtin_token(name: &str) -> Token {
+    Token::new(
+        TokenType::Builtin,
+        name.to_string(),
+        HezenLineInfo::new("<builtin>".to_string(), 0, 0),
+    )
+}
@@ -0,0 +1,280 @@
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    fs::{self, File},
+    io::{BufRead, BufReader, Read, Write},
+    path::Path,
+    rc::Rc,
+};
+
+use hezen_core::error::HezenError;
+
+use crate::{
+    class::HezenClass,
+    environment::HezenValue,
+    function::HezenNativeFunction,
+    instance::HezenInstanceHandle,
+    module::{native_error, native_error_with_kind},
+    token::Token,
+};
+
+/// Natives that read from stdin. Failures are raised as `IOError`s, which
+/// scripts can handle with `try`/`catch`.
+pub fn console_functions() -> Vec<HezenNativeFunction> {
+    vec![
+        HezenNativeFunction::with_interpreter("input", 1, |interpreter, paren, args| {
+            let prompt = string_argument(paren, "input", args, 0)?;
+            let stdout = interpreter.stdout();
+
+            write!(stdout, "{prompt}")
+                .and_then(|_| stdout.flush())
+                .map_err(|why| io_error(paren, "Cannot write to stdout", why))?;
+            read_line(paren)
+        }),
+        HezenNativeFunction::builtin("read_line", 0, |paren, _| read_line(paren)),
+    ]
+}
+
+/// File system natives, raising `IOError`s like [`console_functions`].
+pub fn file_functions() -> Vec<HezenNativeFunction> {
+    let lines = Rc::new(lines_class());
+
+    vec![
+        HezenNativeFunction::with_interpreter("read_file", 1, |interpreter, paren, args| {
+            let path = string_argument(paren, "read_file", args, 0)?;
+            let allowance = interpreter.allowance();
+            let error = |why| io_error(paren, &format!("Cannot read file '{path}'"), why);
+
+            // Read one byte past what the memory limit allows, so that a file
+            // too large for it fails without being read whole.
+            let mut bytes = Vec::new();
+            File::open(path)
+                .and_then(|file| {
+                    file.take(allowance.left().saturating_add(1) as u64)
+                        .read_to_end(&mut bytes)
+                })
+                .map_err(error)?;
+            allowance.check(bytes.len(), paren)?;
+
+            text(bytes).map(HezenValue::String).map_err(error)
+        }),
+        HezenNativeFunction::with_interpreter("read_lines", 1, move |_, paren, args| {
+            let path = string_argument(paren, "read_lines", args, 0)?;
+            let file = File::open(path)
+                .map_err(|why| io_error(paren, &format!("Cannot read file '{path}'"), why))?;
+
+            let instance = HezenInstanceHandle::new(lines.clone());
+            instance.set_data(LineReader {
+                path: path.to_string(),
+                reader: RefCell::new(Some(BufReader::new(file))),
+            });
+
+            Ok(HezenValue::Instance(instance))
+        }),
+        HezenNativeFunction::builtin("write_file", 2, |paren, args| {
+            let path = string_argument(paren, "write_file", args, 0)?;
+            let content = string_argument(paren, "write_file", args, 1)?;
+
+            fs::write(path, content)
+                .map(|_| HezenValue::Nil)
+                .map_err(|why| io_error(paren, &format!("Cannot write file '{path}'"), why))
+        }),
+        HezenNativeFunction::builtin("append_file", 2, |paren, args| {
+            let path = string_argument(paren, "append_file", args, 0)?;
+            let content = string_argument(paren, "append_file", args, 1)?;
+
+            fs::OpenOptions::new()
+                .append(true)
+                .create(true)
+                .open(path)
+                .and_then(|mut file| file.write_all(content.as_bytes()))
+                .map(|_| HezenValue::Nil)
+                .map_err(|why| io_error(paren, &format!("Cannot append to file '{path}'"), why))
+        }),
+        HezenNativeFunction::builtin("remove_file", 1, |paren, args| {
+            let path = string_argument(paren, "remove_file", args, 0)?;
+
+            fs::remove_file(path)
+                .map(|_| HezenValue::Nil)
+                .map_err(|why| io_error(paren, &format!("Cannot remove file '{path}'"), why))
+        }),
+        HezenNativeFunction::builtin("file_exists", 1, |paren, args| {
+            let path = string_argument(paren, "file_exists", args, 0)?;
+
+            Ok(HezenValue::Bool(Path::new(path).exists()))
+        }),
+        HezenNativeFunction::builtin("list_dir", 1, |paren, args| {
+            let path = string_argument(paren, "list_dir", args, 0)?;
+            let error = |why| io_error(paren, &format!("Cannot list directory '{path}'"), why);
+
+            let mut names = Vec::new();
+            for entry in fs::read_dir(path).map_err(error)? {
+                names.push(
+                    entry
+                        .map_err(error)?
+                        .file_name()
+                        .to_string_lossy()
+                        .to_string(),
+                );
+            }
+            names.sort();
+
+            Ok(HezenValue::List(Rc::new(RefCell::new(
+                names.into_iter().map(HezenValue::String).collect(),
+            ))))
+        }),
+    ]
+}
+
+/// The data of a `Lines` instance. The file is closed once it has been read
+/// to the end.
+struct LineReader {
+    path: String,
+    reader: RefCell<Option<BufReader<File>>>,
+}
+
+/// The class of the values `read_lines` returns. Its `next` method reads the
+/// file one line at a time, so that large files are never loaded whole.
+fn lines_class() -> HezenClass {
+    HezenClass::native("Lines").method("next", 0, |interpreter, paren, instance, _| {
+        let Some(lines) = instance.data::<LineReader>() else {
+            return Ok(HezenValue::Nil);
+        };
+        let mut reader = lines.reader.borrow_mut();
+        let Some(file) = reader.as_mut() else {
+            return Ok(HezenValue::Nil);
+        };
+
+        // Like `read_file`, stop one byte past what the memory limit allows.
+        let allowance = interpreter.allowance();
+        let error = |why| io_error(paren, &format!("Cannot read file '{}'", lines.path), why);
+        let line =
+            next_line(&mut file.take(allowance.left().saturating_add(1) as u64)).map_err(error)?;
+        allowance.check(line.as_ref().map_or(0, Vec::len), paren)?;
+
+        let Some(line) = line else {
+            *reader = None;
+            return Ok(HezenValue::Nil);
+        };
+
+        text(line).map(HezenValue::String).map_err(error)
+    })
+}
+
+/// Reads a line from stdin without its line break, or `nil` at end of input.
+fn read_line(paren: &Token) -> Result<HezenValue, HezenError> {
+    next_line(&mut std::io::stdin().lock())
+        .and_then(|line| line.map(text).transpose())
+        .map(|line| line.map_or(HezenValue::Nil, HezenValue::String))
+        .map_err(|why| io_error(paren, "Cannot read from stdin", why))
+}
+
+/// Reads a line without its line break, or `None` at end of input.
+fn next_line(reader: &mut impl BufRead) -> std::io::Result<Option<Vec<u8>>> {
+    let mut line = Vec::new();
+
+    if reader.read_until(b'\n', &mut line)? == 0 {
+        return Ok(None);
+    }
+
+    if line.ends_with(b"\n") {
+        line.pop();
+        if line.ends_with(b"\r") {
+            line.pop();
+        }
+    }
+
+    Ok(Some(line))
+}
+
+/// Decodes what was read, which must be UTF-8.
+fn text(bytes: Vec<u8>) -> std::io::Result<String> {
+    String::from_utf8(bytes).map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )
+    })
+}
+
+fn string_argument<'a>(
+    paren: &Token,
+    function: &str,
+    args: &'a [HezenValue],
+    index: usize,
+) -> Result<&'a str, HezenError> {
+    match &args[index] {
+        HezenValue::String(s) => Ok(s),
+        other => Err(native_error(
+            paren,
+            format!(
+                "Expected argument {} of '{}' to be a string, not '{}'",
+                index + 1,
+                function,
+                other.type_name()
+            ),
+        )),
+    }
+}
+
+pub fn io_error(paren: &Token, context: &str, why: std::io::Error) -> HezenError {
+    native_error_with_kind(paren, "IOError", format!("{context}: {why}"))
+}
+
+/// A destination for the interpreter's output, such as its stdout.
+pub struct Output(Box<dyn Write>);
+
+impl Output {
+    pub fn new(writer: impl Write + 'static) -> Self {
+        Self(Box::new(writer))
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl Debug for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Output")
+    }
+}
+
+/// An in-memory writer whose clones share the same contents, so a host can
+/// hand one to the interpreter and read the output from another.
+#[derive(Debug, Clone, Default)]
+pub struct SharedBuffer {
+    bytes: Rc<RefCell<Vec<u8>>>,
+}
+
+impl SharedBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything written so far, with invalid UTF-8 replaced.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.bytes.borrow()).into_owned()
+    }
+
+    pub fn clear(&self) {
+        self.bytes.borrow_mut().clear();
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.bytes.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
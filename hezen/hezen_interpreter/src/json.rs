@@ -0,0 +1,554 @@
+use std::{cell::RefCell, mem::size_of, rc::Rc};
+
+use hezen_core::error::HezenError;
+use indexmap::IndexMap;
+use num_bigint::BigInt;
+
+use crate::{
+    environment::HezenValue,
+    limits::Allowance,
+    module::{native_error, native_error_with_kind, HezenModule},
+    numeric,
+    token::Token,
+};
+
+/// The `json` namespace. Objects become maps, arrays become lists, and `null`
+/// becomes `nil`. Malformed input raises a `JSONError` naming the line and
+/// column of the problem.
+pub fn module() -> HezenModule {
+    HezenModule::new("json")
+        .function_with_interpreter("parse", 1, |interpreter, paren, args| match &args[0] {
+            HezenValue::String(text) => {
+                JsonParser::new(paren, interpreter.allowance(), text).parse()
+            }
+            other => Err(native_error(
+                paren,
+                format!(
+                    "Expected argument 1 of 'json.parse' to be a string, not '{}'",
+                    other.type_name()
+                ),
+            )),
+        })
+        .function_with_interpreter("stringify", 2, |interpreter, paren, args| {
+            let indent = match &args[1] {
+                HezenValue::Nil => None,
+                HezenValue::Integer(n) if (0..=16).contains(n) => Some(*n as usize),
+                other => {
+                    return Err(native_error(
+                        paren,
+                        format!(
+                            "Expected argument 2 of 'json.stringify' to be nil or an indent between 0 and 16, not '{other}'"
+                        ),
+                    ))
+                }
+            };
+
+            let mut writer = JsonWriter {
+                paren,
+                allowance: interpreter.allowance(),
+                indent: indent.filter(|n| *n > 0),
+                output: String::new(),
+                seen: Vec::new(),
+            };
+            writer.write(&args[0], 0)?;
+
+            Ok(HezenValue::String(writer.output))
+        })
+}
+
+/// How deeply arrays and objects may nest, in both directions. Parsing and
+/// writing recurse once per level, so this keeps deep input from exhausting
+/// the stack.
+const MAX_DEPTH: usize = 512;
+
+struct JsonParser<'a> {
+    /// The call of `json.parse`, which errors are reported at.
+    paren: &'a Token,
+    /// What the values parsed so far may take up.
+    allowance: Allowance,
+    allocated: usize,
+    source: Vec<char>,
+    current: usize,
+    line: usize,
+    column: usize,
+    depth: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(paren: &'a Token, allowance: Allowance, text: &str) -> Self {
+        Self {
+            paren,
+            allowance,
+            allocated: 0,
+            source: text.chars().collect(),
+            current: 0,
+            line: 1,
+            column: 1,
+            depth: 0,
+        }
+    }
+
+    fn parse(mut self) -> Result<HezenValue, HezenError> {
+        self.skip_whitespace();
+        let value = self.value()?;
+        self.skip_whitespace();
+
+        match self.peek() {
+            None => Ok(value),
+            Some(c) => Err(self.error(format!("Unexpected '{c}' after JSON value"))),
+        }
+    }
+
+    fn value(&mut self) -> Result<HezenValue, HezenError> {
+        let value = match self.peek() {
+            Some('{') => self.nested(Self::object),
+            Some('[') => self.nested(Self::array),
+            Some('"') => self.string().map(HezenValue::String),
+            Some('-' | '0'..='9') => self.number(),
+            Some('t') => self.keyword("true", HezenValue::Bool(true)),
+            Some('f') => self.keyword("false", HezenValue::Bool(false)),
+            Some('n') => self.keyword("null", HezenValue::Nil),
+            Some(c) => Err(self.error(format!("Unexpected '{c}'"))),
+            None => Err(self.error("Unexpected end of input".to_string())),
+        }?;
+
+        self.allocated += match &value {
+            HezenValue::String(s) => size_of::<HezenValue>() + s.len(),
+            _ => size_of::<HezenValue>(),
+        };
+        self.allowance.check(self.allocated, self.paren)?;
+
+        Ok(value)
+    }
+
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<HezenValue, HezenError>,
+    ) -> Result<HezenValue, HezenError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(format!("Nesting deeper than {MAX_DEPTH} levels")));
+        }
+
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+
+        value
+    }
+
+    fn object(&mut self) -> Result<HezenValue, HezenError> {
+        self.advance();
+        let mut entries = IndexMap::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.advance();
+            return Ok(HezenValue::Map(Rc::new(RefCell::new(entries))));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("Expected string key in object".to_string()));
+            }
+            let key = self.string()?;
+            self.allocated += size_of::<String>() + key.len();
+
+            self.skip_whitespace();
+            self.expect(':', "Expected ':' after object key")?;
+            self.skip_whitespace();
+            entries.insert(key, self.value()?);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.advance();
+                }
+                Some('}') => {
+                    self.advance();
+                    return Ok(HezenValue::Map(Rc::new(RefCell::new(entries))));
+                }
+                _ => return Err(self.error("Expected ',' or '}' in object".to_string())),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<HezenValue, HezenError> {
+        self.advance();
+        let mut elements = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.advance();
+            return Ok(HezenValue::List(Rc::new(RefCell::new(elements))));
+        }
+
+        loop {
+            self.skip_whitespace();
+            elements.push(self.value()?);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.advance();
+                }
+                Some(']') => {
+                    self.advance();
+                    return Ok(HezenValue::List(Rc::new(RefCell::new(elements))));
+                }
+                _ => return Err(self.error("Expected ',' or ']' in array".to_string())),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, HezenError> {
+        self.advance();
+        let mut value = String::new();
+
+        loop {
+            match self.peek() {
+                None => return Err(self.error("Unterminated string".to_string())),
+                Some('"') => {
+                    self.advance();
+                    return Ok(value);
+                }
+                Some('\\') => {
+                    self.advance();
+                    value.push(self.escape()?);
+                }
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(self.error("Control character in string".to_string()))
+                }
+                Some(c) => {
+                    self.advance();
+                    value.push(c);
+                }
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, HezenError> {
+        let c = match self.peek() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                self.advance();
+                let high = self.hex_code()?;
+
+                if !(0xD800..0xDC00).contains(&high) {
+                    return char::from_u32(high)
+                        .ok_or_else(|| self.error("Invalid unicode escape".to_string()));
+                }
+
+                if self.peek() != Some('\\') || self.peek_at(1) != Some('u') {
+                    return Err(
+                        self.error("Expected low surrogate after high surrogate".to_string())
+                    );
+                }
+                self.advance();
+                self.advance();
+                let low = self.hex_code()?;
+
+                if !(0xDC00..0xE000).contains(&low) {
+                    return Err(self.error("Invalid low surrogate".to_string()));
+                }
+
+                return char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+                    .ok_or_else(|| self.error("Invalid unicode escape".to_string()));
+            }
+            _ => return Err(self.error("Invalid escape sequence".to_string())),
+        };
+
+        self.advance();
+        Ok(c)
+    }
+
+    fn hex_code(&mut self) -> Result<u32, HezenError> {
+        let mut code = 0;
+
+        for _ in 0..4 {
+            let digit = self
+                .peek()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("Expected four hex digits".to_string()))?;
+            code = code * 16 + digit;
+            self.advance();
+        }
+
+        Ok(code)
+    }
+
+    fn number(&mut self) -> Result<HezenValue, HezenError> {
+        let start = self.current;
+        let mut fraction = false;
+
+        if self.peek() == Some('-') {
+            self.advance();
+        }
+
+        match self.peek() {
+            Some('0') => {
+                self.advance();
+            }
+            Some('1'..='9') => self.digits(),
+            _ => return Err(self.error("Expected digit".to_string())),
+        }
+
+        if self.peek() == Some('.') {
+            fraction = true;
+            self.advance();
+            if !matches!(self.peek(), Some('0'..='9')) {
+                return Err(self.error("Expected digit after '.'".to_string()));
+            }
+            self.digits();
+        }
+
+        if matches!(self.peek(), Some('e' | 'E')) {
+            fraction = true;
+            self.advance();
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.advance();
+            }
+            if !matches!(self.peek(), Some('0'..='9')) {
+                return Err(self.error("Expected digit in exponent".to_string()));
+            }
+            self.digits();
+        }
+
+        let text: String = self.source[start..self.current].iter().collect();
+
+        if fraction {
+            Ok(HezenValue::Number(text.parse().unwrap_or(f64::NAN)))
+        } else {
+            Ok(numeric::normalize_bigint(
+                text.parse::<BigInt>().unwrap_or_default(),
+            ))
+        }
+    }
+
+    fn digits(&mut self) {
+        while matches!(self.peek(), Some('0'..='9')) {
+            self.advance();
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: HezenValue) -> Result<HezenValue, HezenError> {
+        for expected in word.chars() {
+            if self.peek() != Some(expected) {
+                return Err(self.error(format!("Expected '{word}'")));
+            }
+            self.advance();
+        }
+
+        Ok(value)
+    }
+
+    fn expect(&mut self, expected: char, message: &str) -> Result<(), HezenError> {
+        if self.peek() != Some(expected) {
+            return Err(self.error(message.to_string()));
+        }
+
+        self.advance();
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.advance();
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.source.get(self.current + offset).copied()
+    }
+
+    fn advance(&mut self) {
+        if self.peek() == Some('\n') {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        self.current += 1;
+    }
+
+    fn error(&self, message: String) -> HezenError {
+        native_error_with_kind(
+            self.paren,
+            "JSONError",
+            format!("{message} at line {}, column {}", self.line, self.column),
+        )
+    }
+}
+
+struct JsonWriter<'a> {
+    /// The call of `json.stringify`, which errors are reported at.
+    paren: &'a Token,
+    /// What the output may take up. Each value adds at most a few times the
+    /// size of strings that already exist, so it is checked once per value.
+    allowance: Allowance,
+    indent: Option<usize>,
+    output: String,
+    /// Addresses of the lists, maps and instances being written, to reject
+    /// values that contain themselves.
+    seen: Vec<usize>,
+}
+
+impl JsonWriter<'_> {
+    fn write(&mut self, value: &HezenValue, depth: usize) -> Result<(), HezenError> {
+        self.allowance.check(self.output.len(), self.paren)?;
+
+        match value {
+            HezenValue::Nil => self.output.push_str("null"),
+            HezenValue::Bool(b) => self.output.push_str(&b.to_string()),
+            HezenValue::Integer(_) | HezenValue::BigInt(_) | HezenValue::Decimal(_) => {
+                self.output.push_str(&value.to_string())
+            }
+            HezenValue::Number(n) if n.is_finite() => self.output.push_str(&n.to_string()),
+            HezenValue::Number(n) => {
+                return Err(native_error(
+                    self.paren,
+                    format!("Cannot convert '{n}' to JSON"),
+                ))
+            }
+            HezenValue::String(s) => self.string(s),
+            HezenValue::Tuple(values) => self.array(values, depth)?,
+            HezenValue::List(values) => {
+                self.enter(Rc::as_ptr(values) as *const () as usize)?;
+                self.array(&values.borrow(), depth)?;
+                self.seen.pop();
+            }
+            HezenValue::Map(entries) => {
+                self.enter(Rc::as_ptr(entries) as *const () as usize)?;
+                let entries: Vec<(String, HezenValue)> = entries
+                    .borrow()
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                self.object(&entries, depth)?;
+                self.seen.pop();
+            }
+            HezenValue::Instance(instance) => {
+                self.enter(instance.address())?;
+                self.object(&instance.fields(), depth)?;
+                self.seen.pop();
+            }
+            other => {
+                return Err(native_error(
+                    self.paren,
+                    format!("Cannot convert '{}' to JSON", other.type_name()),
+                ))
+            }
+        }
+
+        self.allowance.check(self.output.len(), self.paren)
+    }
+
+    /// Fails for an array or object that would be nested too deeply.
+    fn check_depth(&self, depth: usize) -> Result<(), HezenError> {
+        if depth >= MAX_DEPTH {
+            return Err(native_error_with_kind(
+                self.paren,
+                "JSONError",
+                format!("Cannot convert a value nested deeper than {MAX_DEPTH} levels to JSON"),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn enter(&mut self, address: usize) -> Result<(), HezenError> {
+        if self.seen.contains(&address) {
+            return Err(native_error(
+                self.paren,
+                "Cannot convert a value that contains itself to JSON".to_string(),
+            ));
+        }
+
+        self.seen.push(address);
+        Ok(())
+    }
+
+    fn array(&mut self, values: &[HezenValue], depth: usize) -> Result<(), HezenError> {
+        self.check_depth(depth)?;
+        self.output.push('[');
+
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                self.output.push(',');
+            }
+            self.newline(depth + 1);
+            self.write(value, depth + 1)?;
+        }
+
+        if !values.is_empty() {
+            self.newline(depth);
+        }
+        self.output.push(']');
+
+        Ok(())
+    }
+
+    fn object(&mut self, entries: &[(String, HezenValue)], depth: usize) -> Result<(), HezenError> {
+        self.check_depth(depth)?;
+        self.output.push('{');
+
+        for (i, (key, value)) in entries.iter().enumerate() {
+            if i > 0 {
+                self.output.push(',');
+            }
+            self.newline(depth + 1);
+            self.string(key);
+            self.output.push(':');
+            if self.indent.is_some() {
+                self.output.push(' ');
+            }
+            self.write(value, depth + 1)?;
+        }
+
+        if !entries.is_empty() {
+            self.newline(depth);
+        }
+        self.output.push('}');
+
+        Ok(())
+    }
+
+    fn newline(&mut self, depth: usize) {
+        if let Some(indent) = self.indent {
+            self.output.push('\n');
+            self.output.push_str(&" ".repeat(indent * depth));
+        }
+    }
+
+    fn string(&mut self, s: &str) {
+        self.output.push('"');
+
+        for c in s.chars() {
+            match c {
+                '"' => self.output.push_str("\\\""),
+                '\\' => self.output.push_str("\\\\"),
+                '\n' => self.output.push_str("\\n"),
+                '\r' => self.output.push_str("\\r"),
+                '\t' => self.output.push_str("\\t"),
+                '\u{8}' => self.output.push_str("\\b"),
+                '\u{c}' => self.output.push_str("\\f"),
+                c if (c as u32) < 0x20 => self.output.push_str(&format!("\\u{:04x}", c as u32)),
+                c => self.output.push(c),
+            }
+        }
+
+        self.output.push('"');
+    }
+}
@@ -1,22 +1,21 @@
 use crate::token::{Token, TokenType, Tokens};
 use hezen_core::error::{HezenError, HezenErrorList, HezenLineInfo};
-use once_cell::sync::Lazy;
-
-static NEWLINE_REGEX: Lazy<regex::Regex> =
-    Lazy::new(|| regex::Regex::new(r"(^|[^\\])(\\n)").unwrap());
-
-static TAB_REGEX: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"(^|[^\\])(\\t)").unwrap());
-
-static BACKSLASH_REGEX: Lazy<regex::Regex> = Lazy::new(|| regex::Regex::new(r"(\\\\)").unwrap());
+use rust_decimal::Decimal;
 
 pub struct Lexer<'a> {
     filename: String,
-    source: String,
+    source: Vec<char>,
     tokens: Tokens,
     start: usize,
+    /// The line and column of `start`.
+    start_line: usize,
+    start_column: usize,
     current: usize,
     line: usize,
     line_start: usize,
+    /// Brace depth of each string interpolation the lexer is currently inside,
+    /// and whether the string it resumes afterwards is triple-quoted.
+    interpolations: Vec<(usize, bool)>,
     errors: &'a mut HezenErrorList,
 }
 
@@ -24,12 +23,15 @@ impl<'a> Lexer<'a> {
     pub fn new(filename: String, source: String, errors: &'a mut HezenErrorList) -> Self {
         Self {
             filename,
-            source,
+            source: source.chars().collect(),
             tokens: Tokens::new(),
             start: 0,
+            start_line: 1,
+            start_column: 1,
             current: 0,
             line: 1,
             line_start: 0,
+            interpolations: Vec::new(),
             errors,
         }
     }
@@ -37,9 +39,15 @@ impl<'a> Lexer<'a> {
     pub fn get_tokens(mut self) -> Tokens {
         while !self.is_at_end() {
             self.start = self.current;
+            self.start_line = self.line;
+            self.start_column = self.start - self.line_start + 1;
             self.get_token();
         }
 
+        if !self.interpolations.is_empty() {
+            self.error("Unterminated string interpolation.");
+        }
+
         self.tokens.add(Token::new(
             TokenType::Eof,
             "".to_string(),
@@ -59,14 +67,82 @@ impl<'a> Lexer<'a> {
         match c {
             '(' => self.add_token(TokenType::LeftParen),
             ')' => self.add_token(TokenType::RightParen),
-            '{' => self.add_token(TokenType::LeftBrace),
-            '}' => self.add_token(TokenType::RightBrace),
+            '{' => {
+                if let Some((depth, _)) = self.interpolations.last_mut() {
+                    *depth += 1;
+                }
+                self.add_token(TokenType::LeftBrace)
+            }
+            '}' => match self.interpolations.last_mut() {
+                Some((0, triple)) => {
+                    let triple = *triple;
+                    self.interpolations.pop();
+                    self.handle_string(triple);
+                }
+                Some((depth, _)) => {
+                    *depth -= 1;
+                    self.add_token(TokenType::RightBrace)
+                }
+                None => self.add_token(TokenType::RightBrace),
+            },
+            '[' => self.add_token(TokenType::LeftBracket),
+            ']' => self.add_token(TokenType::RightBracket),
             ',' => self.add_token(TokenType::Comma),
-            '.' => self.add_token(TokenType::Dot),
-            '-' => self.add_token(TokenType::Minus),
-            '+' => self.add_token(TokenType::Plus),
+            '.' => {
+                if self.try_match('.') {
+                    if self.try_match('=') {
+                        self.add_token(TokenType::DotDotEqual)
+                    } else if self.try_match('.') {
+                        self.add_token(TokenType::DotDotDot)
+                    } else {
+                        self.add_token(TokenType::DotDot)
+                    }
+                } else {
+                    self.add_token(TokenType::Dot)
+                }
+            }
+            '|' => self.add_token(TokenType::Pipe),
+            '-' => {
+                if self.try_match('=') {
+                    self.add_token(TokenType::MinusEqual)
+                } else {
+                    self.add_token(TokenType::Minus)
+                }
+            }
+            '+' => {
+                if self.try_match('=') {
+                    self.add_token(TokenType::PlusEqual)
+                } else {
+                    self.add_token(TokenType::Plus)
+                }
+            }
             ';' => self.add_token(TokenType::Semicolon),
-            '*' => self.add_token(TokenType::Star),
+            ':' => self.add_token(TokenType::Colon),
+            '?' => {
+                if self.try_match('?') {
+                    self.add_token(TokenType::QuestionQuestion)
+                } else if self.try_match('.') {
+                    self.add_token(TokenType::QuestionDot)
+                } else {
+                    self.add_token(TokenType::Question)
+                }
+            }
+            '*' => {
+                if self.try_match('*') {
+                    self.add_token(TokenType::StarStar)
+                } else if self.try_match('=') {
+                    self.add_token(TokenType::StarEqual)
+                } else {
+                    self.add_token(TokenType::Star)
+                }
+            }
+            '%' => {
+                if self.try_match('=') {
+                    self.add_token(TokenType::PercentEqual)
+                } else {
+                    self.add_token(TokenType::Percent)
+                }
+            }
             '!' => {
                 if self.try_match('=') {
                     self.add_token(TokenType::BangEqual)
@@ -77,6 +153,8 @@ impl<'a> Lexer<'a> {
             '=' => {
                 if self.try_match('=') {
                     self.add_token(TokenType::EqualEqual)
+                } else if self.try_match('>') {
+                    self.add_token(TokenType::FatArrow)
                 } else {
                     self.add_token(TokenType::Equal)
                 }
@@ -97,9 +175,22 @@ impl<'a> Lexer<'a> {
             }
             '/' => {
                 if self.try_match('/') {
+                    let doc = self.peek() == '/' && self.peek_next() != '/';
+
                     while self.peek() != '\n' && !self.is_at_end() {
                         self.advance();
                     }
+
+                    if doc {
+                        let text = self.text(self.start + 3, self.current);
+                        let text = text.strip_prefix(' ').unwrap_or(&text).trim_end();
+
+                        self.add_token(TokenType::DocComment(text.to_string()));
+                    }
+                } else if self.try_match('*') {
+                    self.handle_block_comment();
+                } else if self.try_match('=') {
+                    self.add_token(TokenType::SlashEqual)
                 } else {
                     self.add_token(TokenType::Slash)
                 }
@@ -109,7 +200,16 @@ impl<'a> Lexer<'a> {
                 self.line += 1;
                 self.line_start = self.current;
             }
-            '"' => self.handle_string(),
+            '"' => {
+                let triple = self.peek() == '"' && self.peek_next() == '"';
+                if triple {
+                    self.advance();
+                    self.advance();
+                    self.skip_leading_newline();
+                }
+                self.handle_string(triple)
+            }
+            'r' if self.peek() == '"' => self.handle_raw_string(),
             '0'..='9' => self.handle_number(),
             'a'..='z' | 'A'..='Z' | '_' => self.handle_identifier(),
             _ => {
@@ -119,7 +219,7 @@ impl<'a> Lexer<'a> {
     }
 
     fn add_token(&mut self, token: TokenType) {
-        let text = self.source[self.start..self.current].to_string();
+        let text = self.text(self.start, self.current);
         self.tokens.add(Token::new(
             token,
             text,
@@ -127,29 +227,33 @@ impl<'a> Lexer<'a> {
                 self.filename.clone(),
                 self.line,
                 self.current - self.line_start,
-            ),
+            )
+            .starting_at(self.start_line, self.start_column),
         ));
     }
 
+    fn text(&self, start: usize, end: usize) -> String {
+        self.source[start..end].iter().collect()
+    }
+
     fn advance(&mut self) -> char {
         self.current += 1;
-        self.source.chars().nth(self.current - 1).unwrap()
+        self.source[self.current - 1]
     }
 
     fn peek(&self) -> char {
-        if self.is_at_end() {
-            '\0'
-        } else {
-            self.source.chars().nth(self.current).unwrap()
-        }
+        self.peek_at(0)
     }
 
     fn peek_next(&self) -> char {
-        if self.current + 1 >= self.source.len() {
-            '\0'
-        } else {
-            self.source.chars().nth(self.current + 1).unwrap()
-        }
+        self.peek_at(1)
+    }
+
+    fn peek_at(&self, offset: usize) -> char {
+        self.source
+            .get(self.current + offset)
+            .copied()
+            .unwrap_or('\0')
     }
 
     fn try_match(&mut self, c: char) -> bool {
@@ -157,7 +261,7 @@ impl<'a> Lexer<'a> {
             return false;
         }
 
-        if self.source.chars().nth(self.current).unwrap() != c {
+        if self.source[self.current] != c {
             return false;
         }
 
@@ -169,29 +273,198 @@ impl<'a> Lexer<'a> {
         self.current >= self.source.len()
     }
 
-    fn handle_string(&mut self) {
-        while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
+    /// Lexes a string literal, or the rest of one after an interpolated
+    /// expression. Every `${` ends the current piece with an `Interpolation`
+    /// token; the lexer then reads the expression and resumes the string at
+    /// the matching `}`.
+    fn handle_string(&mut self, triple: bool) {
+        let mut value = String::new();
+
+        loop {
+            if self.is_at_end() {
+                self.error("Unterminated string.");
+                return;
+            }
+
+            if self.at_closing_quote(triple) {
+                break;
+            }
+
+            if self.peek() == '$' && self.peek_next() == '{' {
+                self.advance();
+                self.advance();
+                self.add_token(TokenType::Interpolation(value));
+                self.interpolations.push((0, triple));
+                return;
+            }
+
+            match self.advance() {
+                '\\' => self.handle_escape(&mut value),
+                c => {
+                    if c == '\n' {
+                        self.line += 1;
+                        self.line_start = self.current;
+                    }
+                    value.push(c);
+                }
+            }
+        }
+
+        self.add_token(TokenType::String(value));
+    }
+
+    /// Lexes `r"..."` and `r"""..."""`, which keep backslashes and `${` as
+    /// they are written.
+    fn handle_raw_string(&mut self) {
+        self.advance();
+
+        let triple = self.peek() == '"' && self.peek_next() == '"';
+        if triple {
+            self.advance();
+            self.advance();
+            self.skip_leading_newline();
+        }
+
+        let mut value = String::new();
+
+        loop {
+            if self.is_at_end() {
+                self.error("Unterminated string.");
+                return;
+            }
+
+            if self.at_closing_quote(triple) {
+                break;
+            }
+
+            let c = self.advance();
+            if c == '\n' {
                 self.line += 1;
                 self.line_start = self.current;
             }
-            self.advance();
+            value.push(c);
         }
 
-        if self.is_at_end() {
-            self.error("Unterminated string.");
-            return;
+        self.add_token(TokenType::String(value));
+    }
+
+    /// Consumes the closing delimiter of a string if it comes next.
+    fn at_closing_quote(&mut self, triple: bool) -> bool {
+        if triple {
+            if self.peek() == '"' && self.peek_next() == '"' && self.peek_at(2) == '"' {
+                self.current += 3;
+                return true;
+            }
+
+            false
+        } else {
+            self.try_match('"')
         }
+    }
+
+    /// A triple-quoted string that starts on its own line does not include
+    /// that first line break.
+    fn skip_leading_newline(&mut self) {
+        self.try_match('\r');
+
+        if self.try_match('\n') {
+            self.line += 1;
+            self.line_start = self.current;
+        }
+    }
+
+    /// Decodes the escape sequence following a backslash into `value`.
+    fn handle_escape(&mut self, value: &mut String) {
+        let column = self.current - self.line_start;
+
+        let c = match self.peek() {
+            '"' => '"',
+            '\\' => '\\',
+            '$' => '$',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            'x' => {
+                self.advance();
+
+                let digits = self.take_while(2, |c| c.is_ascii_hexdigit());
+                match u8::from_str_radix(&digits, 16) {
+                    Ok(byte) if digits.len() == 2 && byte.is_ascii() => value.push(byte as char),
+                    _ => self.error_at(
+                        column,
+                        "Invalid escape sequence, '\\x' must be followed by two hex digits up to 7F.",
+                    ),
+                }
+                return;
+            }
+            'u' => {
+                self.advance();
+
+                if !self.try_match('{') {
+                    self.error_at(column, "Invalid escape sequence, expected '{' after '\\u'.");
+                    return;
+                }
+
+                let digits = self.take_while(6, |c| c.is_ascii_hexdigit());
+                let c = u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32);
+
+                match c {
+                    Some(c) if self.try_match('}') => value.push(c),
+                    _ => self.error_at(
+                        column,
+                        "Invalid unicode escape, expected '\\u{XXXX}' with 1 to 6 hex digits.",
+                    ),
+                }
+                return;
+            }
+            c => {
+                self.error_at(column, &format!("Invalid escape sequence '\\{c}'."));
+                if !self.is_at_end() && c != '\n' {
+                    self.advance();
+                }
+                return;
+            }
+        };
 
         self.advance();
+        value.push(c);
+    }
+
+    fn take_while(&mut self, max: usize, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.current;
+
+        while self.current - start < max && !self.is_at_end() && predicate(self.peek()) {
+            self.advance();
+        }
+
+        self.text(start, self.current)
+    }
 
-        let value = self.source[self.start + 1..self.current - 1].to_string();
+    /// Skips a `/* ... */` comment. Block comments nest, so every `/*` inside
+    /// needs its own `*/`.
+    fn handle_block_comment(&mut self) {
+        let mut depth = 1;
 
-        let value = NEWLINE_REGEX.replace_all(&value, "$1\n");
-        let value = TAB_REGEX.replace_all(&value, "$1\t");
-        let value = BACKSLASH_REGEX.replace_all(&value, "$1\\");
+        while depth > 0 {
+            if self.is_at_end() {
+                self.error("Unterminated block comment.");
+                return;
+            }
 
-        self.add_token(TokenType::String(value.to_string()));
+            if self.peek() == '/' && self.peek_next() == '*' {
+                self.current += 2;
+                depth += 1;
+            } else if self.peek() == '*' && self.peek_next() == '/' {
+                self.current += 2;
+                depth -= 1;
+            } else if self.advance() == '\n' {
+                self.line += 1;
+                self.line_start = self.current;
+            }
+        }
     }
 
     fn handle_number(&mut self) {
@@ -199,7 +472,10 @@ impl<'a> Lexer<'a> {
             self.advance();
         }
 
+        let mut is_float = false;
+
         if self.peek() == '.' && self.peek_next().is_ascii_digit() {
+            is_float = true;
             self.advance();
 
             while self.peek().is_ascii_digit() {
@@ -207,8 +483,22 @@ impl<'a> Lexer<'a> {
             }
         }
 
-        let value = &self.source[self.start..self.current];
-        self.add_token(TokenType::Number(value.parse().unwrap()));
+        let value = self.text(self.start, self.current);
+
+        if self.peek() == 'd' && !(self.peek_next().is_alphanumeric() || self.peek_next() == '_') {
+            self.advance();
+
+            match value.parse::<Decimal>() {
+                Ok(d) => self.add_token(TokenType::Decimal(d)),
+                Err(_) => self.error("Decimal literal out of range."),
+            }
+        } else if is_float {
+            self.add_token(TokenType::Number(value.parse().unwrap()));
+        } else if let Ok(i) = value.parse::<i64>() {
+            self.add_token(TokenType::Integer(i));
+        } else {
+            self.add_token(TokenType::BigInt(value.parse().unwrap()));
+        }
     }
 
     fn handle_identifier(&mut self) {
@@ -216,9 +506,9 @@ impl<'a> Lexer<'a> {
             self.advance();
         }
 
-        let text = &self.source[self.start..self.current];
+        let text = self.text(self.start, self.current);
 
-        let token = match text {
+        let token = match text.as_str() {
             "and" => TokenType::And,
             "class" => TokenType::Class,
             "else" => TokenType::Else,
@@ -237,6 +527,10 @@ impl<'a> Lexer<'a> {
             "while" => TokenType::While,
             "break" => TokenType::Break,
             "continue" => TokenType::Continue,
+            "match" => TokenType::Match,
+            "enum" => TokenType::Enum,
+            "try" => TokenType::Try,
+            "catch" => TokenType::Catch,
             _ => TokenType::Identifier,
         };
 
@@ -244,10 +538,14 @@ impl<'a> Lexer<'a> {
     }
 
     fn error(&mut self, message: &str) {
+        self.error_at(self.current - self.line_start, message);
+    }
+
+    fn error_at(&mut self, column: usize, message: &str) {
         self.errors.add(HezenError::syntax_error(
             self.filename.clone(),
             self.line,
-            self.current - self.line_start,
+            column,
             message.to_string(),
         ));
     }
@@ -1,21 +1,57 @@
-use std::io::Write;
-
-use hezen_core::{error::HezenErrorList, Verbosity};
+use hezen_core::{ErrorFormat, Outcome, Verbosity};
 
 mod ast;
+mod cancellation;
+mod capabilities;
 mod class;
+mod convert;
+mod engine;
+mod enumeration;
 mod environment;
+mod exception;
 mod function;
 mod instance;
 mod interpreter;
+mod io;
+mod json;
 mod lexer;
+mod limits;
+mod math;
+mod module;
+mod numeric;
 mod parser;
+mod process;
+mod regex;
 mod resolver;
+mod string;
 mod token;
 
-pub fn run(filename: String, code: String, verbosity: Verbosity) -> Result<(), HezenErrorList> {
+pub use cancellation::CancellationToken;
+pub use capabilities::{Capability, InterpreterBuilder};
+pub use class::HezenClass;
+#[doc(hidden)]
+pub use convert::argument as convert_argument;
+pub use convert::{FromHezen, IntoHezen, NativeReturn};
+pub use engine::Engine;
+pub use environment::HezenValue;
+pub use function::{HezenNativeFunction, NativeFn};
+pub use hezen_core::error::{HezenError, HezenErrorList};
+pub use instance::HezenInstanceHandle;
+pub use interpreter::Interpreter;
+pub use io::SharedBuffer;
+pub use limits::Limits;
+pub use token::Token;
+
+pub fn run(
+    filename: String,
+    code: String,
+    args: Vec<String>,
+    verbosity: Verbosity,
+    error_format: ErrorFormat,
+) -> Result<Outcome, HezenErrorList> {
     let mut pre_run_errors = HezenErrorList::default();
 
+    let source = code.clone();
     let lexer = lexer::Lexer::new(filename, code, &mut pre_run_errors);
 
     let tokens = lexer.get_tokens();
@@ -43,7 +79,7 @@ pub fn run(filename: String, code: String, verbosity: Verbosity) -> Result<(), H
         }
     }
 
-    let mut interpreter = interpreter::Interpreter::new();
+    let mut interpreter = InterpreterBuilder::new().allow_all().args(args).build();
 
     let mut resolver = resolver::Resolver::new(&mut interpreter, &mut pre_run_errors);
 
@@ -58,33 +94,157 @@ pub fn run(filename: String, code: String, verbosity: Verbosity) -> Result<(), H
         }
     }
 
+    let warnings = pre_run_errors.take_warnings();
+
+    if !warnings.is_empty() {
+        let mut buffer = String::new();
+        match error_format {
+            ErrorFormat::Human => warnings.print_details(&mut buffer, &*source).unwrap(),
+            ErrorFormat::Json => warnings.print_json(&mut buffer).unwrap(),
+        }
+        write!(interpreter.stderr(), "{buffer}").unwrap();
+    }
+
     if !pre_run_errors.is_empty() {
         return Err(pre_run_errors);
     }
 
-    let result = interpreter.interpret(&ast);
+    interpreter.interpret(&ast).map_err(HezenErrorList::from)?;
+
+    Ok(match interpreter.exit_code() {
+        Some(code) => Outcome::Exited(code),
+        None => Outcome::Finished,
+    })
+}
+
+/// Renders the doc comments of the top-level functions and classes in `code`,
+/// along with their methods, as Markdown.
+pub fn document(filename: String, code: String) -> Result<String, HezenErrorList> {
+    let mut errors = HezenErrorList::default();
+
+    let lexer = lexer::Lexer::new(filename.clone(), code, &mut errors);
+
+    let tokens = lexer.get_tokens();
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let parser = parser::Parser::new(tokens, &mut errors);
 
-    if let Err(error) = result {
-        return Err(HezenErrorList::from(error));
+    let ast = parser.parse();
+
+    if !errors.is_empty() {
+        return Err(errors);
     }
 
-    Ok(())
+    let mut output = format!("# {filename}\n");
+
+    for stmt in ast.iter() {
+        match stmt {
+            ast::Stmt::Function(name, params, body, doc) => {
+                document_item(&mut output, "##", &signature(name, params, body), doc);
+            }
+            ast::Stmt::Class(name, superclass, methods, doc) => {
+                let heading = match superclass {
+                    Some(ast::Expr::Variable(superclass)) => {
+                        format!("class {} < {}", name.lexeme, superclass.lexeme)
+                    }
+                    _ => format!("class {}", name.lexeme),
+                };
+
+                document_item(&mut output, "##", &heading, doc);
+
+                for method in methods {
+                    if let ast::Stmt::Function(method, params, body, doc) = method {
+                        let signature = signature(method, params, body);
+                        document_item(
+                            &mut output,
+                            "###",
+                            &format!("{}.{}", name.lexeme, signature),
+                            doc,
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(output)
+}
+
+fn signature(name: &token::Token, params: &[token::Token], body: &ast::Stmt) -> String {
+    let statements = match body {
+        ast::Stmt::Block(statements) => statements.as_slice(),
+        _ => &[],
+    };
+
+    // Destructured parameters are passed in under a `#` name and taken apart
+    // at the start of the body, so show the pattern they are taken apart by.
+    let parameter = |p: &token::Token| {
+        statements
+            .iter()
+            .find_map(|stmt| match stmt {
+                ast::Stmt::Destructure(target, ast::Expr::Variable(source), _)
+                    if p.lexeme.starts_with('#') && source.lexeme == p.lexeme =>
+                {
+                    Some(target.to_string())
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| p.lexeme.clone())
+    };
+
+    format!(
+        "{}({})",
+        name.lexeme,
+        params
+            .iter()
+            .map(parameter)
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+fn document_item(output: &mut String, level: &str, heading: &str, doc: &Option<String>) {
+    output.push_str(&format!("\n{level} `{heading}`\n"));
+
+    if let Some(doc) = doc {
+        output.push_str(&format!("\n{doc}\n"));
+    }
 }
 
 pub fn shell() {
-    println!("Hezen Interpreter");
-    println!("Type 'exit' to exit the shell");
-    println!("Type 'help' to get help");
+    let mut engine = Engine::new();
 
-    let mut interpreter = interpreter::Interpreter::new();
+    // Ctrl-C stops the input that is running instead of ending the session.
+    let cancellation = engine.cancellation_token();
+    if let Err(error) = ctrlc::set_handler(move || cancellation.cancel()) {
+        writeln!(engine.stderr(), "Cannot handle Ctrl-C: {error}").unwrap();
+    }
+
+    shell_with(&mut engine, std::io::stdin().lock());
+}
+
+/// Runs a shell session on `engine` that reads its lines from `lines`. The
+/// prompts and script output go to the engine's stdout, and warnings and
+/// errors to its stderr.
+pub fn shell_with(engine: &mut Engine, mut lines: impl std::io::BufRead) {
+    writeln!(engine.stdout(), "Hezen Interpreter").unwrap();
+    writeln!(engine.stdout(), "Type 'exit' to exit the shell").unwrap();
+    writeln!(engine.stdout(), "Type 'help' to get help").unwrap();
+    let mut doc_comments = String::new();
 
     loop {
         let mut input = String::new();
 
-        print!("> ");
-        std::io::stdout().flush().unwrap();
+        write!(engine.stdout(), "> ").unwrap();
+        engine.stdout().flush().unwrap();
 
-        std::io::stdin().read_line(&mut input).unwrap();
+        if lines.read_line(&mut input).unwrap() == 0 {
+            break;
+        }
 
         let input = input.trim();
 
@@ -93,52 +253,59 @@ pub fn shell() {
         }
 
         if input == "help" {
-            println!("Type 'exit' to exit the shell");
-            println!("Type 'help' to get help");
+            let stdout = engine.stdout();
+            writeln!(stdout, "Type 'exit' to exit the shell").unwrap();
+            writeln!(stdout, "Type 'help' to get help").unwrap();
+            writeln!(
+                stdout,
+                "Type 'help <name>' to show the documentation of a function, class or method"
+            )
+            .unwrap();
+            writeln!(stdout, "Press Ctrl-C to interrupt a running input").unwrap();
             continue;
         }
 
-        let mut pre_run_errors = HezenErrorList::default();
-
-        let lexer = lexer::Lexer::new("shell".to_string(), input.to_string(), &mut pre_run_errors);
-
-        let tokens = lexer.get_tokens();
+        if let Some(name) = input.strip_prefix("help ") {
+            let name = name.trim();
 
-        if !pre_run_errors.is_empty() {
-            let mut buffer = String::new();
-            pre_run_errors.print_details(&mut buffer, input).unwrap();
-            eprintln!("{buffer}");
+            match engine.documentation(name) {
+                Some(doc) => writeln!(engine.stdout(), "{doc}").unwrap(),
+                None => writeln!(engine.stdout(), "No documentation found for '{name}'").unwrap(),
+            }
             continue;
         }
 
-        let parser = parser::Parser::new(tokens, &mut pre_run_errors);
-
-        let ast = parser.parse();
-
-        if !pre_run_errors.is_empty() {
-            let mut buffer = String::new();
-            pre_run_errors.print_details(&mut buffer, input).unwrap();
-            eprintln!("{buffer}");
+        // Doc comments are entered on their own lines, so hold on to them
+        // until the declaration they document arrives.
+        if input.starts_with("///") {
+            doc_comments.push_str(input);
+            doc_comments.push('\n');
             continue;
         }
 
-        let mut resolver = resolver::Resolver::new(&mut interpreter, &mut pre_run_errors);
+        let input = format!("{}{input}", std::mem::take(&mut doc_comments));
+        let input = input.as_str();
+
+        // Forget a Ctrl-C pressed at the prompt rather than while running.
+        engine.cancellation_token().reset();
+        let result = engine.eval_named("shell", input);
 
-        resolver.resolve(&ast);
+        let warnings = engine.take_warnings();
 
-        if !pre_run_errors.is_empty() {
+        if !warnings.is_empty() {
             let mut buffer = String::new();
-            pre_run_errors.print_details(&mut buffer, input).unwrap();
-            eprintln!("{buffer}");
-            continue;
+            warnings.print_details(&mut buffer, input).unwrap();
+            write!(engine.stderr(), "{buffer}").unwrap();
         }
 
-        let result = interpreter.interpret(&ast);
-
-        if let Err(error) = result {
+        if let Err(errors) = result {
             let mut buffer = String::new();
-            error.print_details(&mut buffer, input).unwrap();
-            eprintln!("{buffer}");
+            errors.print_details(&mut buffer, input).unwrap();
+            writeln!(engine.stderr(), "{buffer}").unwrap();
+        }
+
+        if engine.exit_code().is_some() {
+            break;
         }
     }
 }
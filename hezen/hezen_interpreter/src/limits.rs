@@ -0,0 +1,338 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Write},
+    mem::size_of,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use hezen_core::error::HezenError;
+use rust_decimal::Decimal;
+
+use crate::{
+    cancellation::CancellationToken, environment::HezenValue, module::native_error_with_kind,
+    token::Token,
+};
+
+/// Bounds on how much work a script may do. Each limit that is hit raises its
+/// own kind of runtime error, which scripts can catch like any other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Limits {
+    /// The number of statements and loop iterations a run may execute.
+    pub instructions: Option<u64>,
+    /// How deeply calls may nest.
+    pub call_depth: Option<usize>,
+    /// How long a run may take.
+    pub time: Option<Duration>,
+    /// Roughly how many bytes a run may allocate. Memory is counted when
+    /// values, variables, fields, instances and calls are created and is not
+    /// given back when they are dropped.
+    pub memory: Option<usize>,
+}
+
+impl Limits {
+    /// No limits at all, not even on call depth.
+    pub fn none() -> Self {
+        Self {
+            instructions: None,
+            call_depth: None,
+            time: None,
+            memory: None,
+        }
+    }
+}
+
+/// Only the call depth is limited by default, so that runaway recursion
+/// fails with an error instead of exhausting memory.
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            call_depth: Some(1000),
+            ..Self::none()
+        }
+    }
+}
+
+/// How often, in statements, the clock is read.
+const CLOCK_INTERVAL: u64 = 256;
+
+/// Tracks a run against its [`Limits`].
+#[derive(Debug, Default)]
+pub(crate) struct Budget {
+    pub limits: Limits,
+    instructions: u64,
+    deadline: Option<Instant>,
+    /// Set once the deadline has passed, so that a caught `TimeoutError`
+    /// cannot resume the run.
+    timed_out: bool,
+    pub call_depth: usize,
+    pub allocated: usize,
+    pub cancellation: CancellationToken,
+}
+
+impl Budget {
+    /// Starts counting a new run from zero.
+    pub fn start(&mut self) {
+        self.instructions = 0;
+        self.allocated = 0;
+        self.timed_out = false;
+        self.deadline = self.limits.time.map(|time| Instant::now() + time);
+    }
+
+    /// Accounts for one statement, reporting an exceeded limit at `at`.
+    pub fn step(&mut self, at: &Token) -> Result<(), HezenError> {
+        self.instructions += 1;
+
+        if let Some(limit) = self.limits.instructions {
+            if self.instructions > limit {
+                return Err(native_error_with_kind(
+                    at,
+                    "InstructionLimitError",
+                    format!("Instruction limit of {limit} exceeded"),
+                ));
+            }
+        }
+
+        if self.timed_out || self.instructions.is_multiple_of(CLOCK_INTERVAL) {
+            self.check_deadline(at)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fails once the deadline has passed, and from then on for the rest of
+    /// the run.
+    fn check_deadline(&mut self, at: &Token) -> Result<(), HezenError> {
+        if let (Some(deadline), Some(time)) = (self.deadline, self.limits.time) {
+            if self.timed_out || Instant::now() > deadline {
+                self.timed_out = true;
+                return Err(native_error_with_kind(
+                    at,
+                    "TimeoutError",
+                    format!("Time limit of {}ms exceeded", time.as_millis()),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Accounts for allocating `bytes`.
+    pub fn charge(&mut self, bytes: usize, at: &Token) -> Result<(), HezenError> {
+        self.allocated = self.allocated.saturating_add(bytes);
+
+        match self.limits.memory {
+            Some(limit) if self.allocated > limit => Err(memory_error(limit, at)),
+            _ => Ok(()),
+        }
+    }
+
+    /// What is left of the memory limit.
+    pub fn allowance(&self) -> Allowance {
+        Allowance {
+            cancellation: self.cancellation.clone(),
+            limit: self.limits.memory,
+            left: self
+                .limits
+                .memory
+                .map_or(usize::MAX, |limit| limit.saturating_sub(self.allocated)),
+        }
+    }
+
+    /// Fails if the run was cancelled, clearing the cancellation.
+    pub fn check_cancelled(&self, at: &Token) -> Result<(), HezenError> {
+        self.cancellation.check(at)
+    }
+
+    /// Accounts for entering the call whose closing parenthesis is `paren`;
+    /// pair with [`Budget::exit`].
+    pub fn enter(&mut self, paren: &Token) -> Result<(), HezenError> {
+        self.check_cancelled(paren)?;
+
+        if self.timed_out {
+            self.check_deadline(paren)?;
+        }
+
+        if let Some(limit) = self.limits.call_depth {
+            if self.call_depth >= limit {
+                return Err(native_error_with_kind(
+                    paren,
+                    "CallDepthError",
+                    format!("Maximum call depth of {limit} exceeded"),
+                ));
+            }
+        }
+
+        self.call_depth += 1;
+        Ok(())
+    }
+
+    pub fn exit(&mut self) {
+        self.call_depth -= 1;
+    }
+}
+
+/// What is left of the memory limit when a native starts. Natives that build
+/// values whose size the arguments do not bound check it before and while
+/// allocating; the value they return is charged as usual. Each check also
+/// stops the native if the run was cancelled in the meantime.
+#[derive(Debug, Clone)]
+pub(crate) struct Allowance {
+    cancellation: CancellationToken,
+    limit: Option<usize>,
+    left: usize,
+}
+
+impl Allowance {
+    /// How many more bytes may be allocated.
+    pub fn left(&self) -> usize {
+        self.left
+    }
+
+    /// Fails with a `MemoryError` at `at` if `bytes` do not fit, or with an
+    /// `Interrupted` error if the run was cancelled.
+    pub fn check(&self, bytes: usize, at: &Token) -> Result<(), HezenError> {
+        self.cancellation.check(at)?;
+
+        match self.limit {
+            Some(limit) if bytes > self.left => Err(memory_error(limit, at)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Starts a string that is checked against what is left as it grows.
+    pub fn text(self) -> BoundedText {
+        BoundedText {
+            allowance: self,
+            text: String::new(),
+        }
+    }
+}
+
+/// A string built from values of any size, such as the text of a string
+/// interpolation, that fails as soon as it outgrows its [`Allowance`] rather
+/// than once it is complete.
+pub(crate) struct BoundedText {
+    allowance: Allowance,
+    text: String,
+}
+
+impl BoundedText {
+    /// Appends `value` as it displays, failing with a `MemoryError` at `at`
+    /// when the text would no longer fit, or with an `Interrupted` error if
+    /// the run is cancelled meanwhile.
+    pub fn push(&mut self, value: &dyn Display, at: &Token) -> Result<(), HezenError> {
+        struct Limited<'a> {
+            text: &'a mut String,
+            left: usize,
+            cancellation: &'a CancellationToken,
+        }
+
+        impl fmt::Write for Limited<'_> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                if self.text.len().saturating_add(s.len()) > self.left
+                    || self.cancellation.is_cancelled()
+                {
+                    return Err(fmt::Error);
+                }
+
+                self.text.push_str(s);
+                Ok(())
+            }
+        }
+
+        let mut limited = Limited {
+            text: &mut self.text,
+            left: self.allowance.left,
+            cancellation: &self.allowance.cancellation,
+        };
+
+        write!(limited, "{value}").map_err(|_| match self.allowance.cancellation.check(at) {
+            Err(interrupted) => interrupted,
+            Ok(()) => memory_error(self.allowance.limit.unwrap_or(0), at),
+        })
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+}
+
+fn memory_error(limit: usize, at: &Token) -> HezenError {
+    native_error_with_kind(
+        at,
+        "MemoryError",
+        format!("Memory limit of {limit} bytes exceeded"),
+    )
+}
+
+/// The approximate cost of a new scope, such as the one a call binds its
+/// parameters in, before its variables.
+pub(crate) const ENVIRONMENT_SIZE: usize = 64;
+
+/// The approximate cost of a variable, field or map entry named `name`.
+pub(crate) fn entry_size(name: &str) -> usize {
+    size_of::<String>() + name.len() + size_of::<HezenValue>()
+}
+
+/// The approximate cost of a new instance, before its fields.
+pub(crate) const INSTANCE_SIZE: usize = 96;
+
+/// The approximate size of `value` itself, without the values it contains.
+pub(crate) fn shallow_size(value: &HezenValue) -> usize {
+    size_of::<HezenValue>()
+        + match value {
+            HezenValue::String(s) => s.len(),
+            HezenValue::Tuple(values) => values.len() * size_of::<HezenValue>(),
+            HezenValue::List(values) => values.borrow().len() * size_of::<HezenValue>(),
+            HezenValue::Map(entries) => entries.borrow().keys().map(|key| entry_size(key)).sum(),
+            _ => number_size(value),
+        }
+}
+
+/// The approximate size of the digits of a bigint or decimal, which
+/// arithmetic results are charged for.
+pub(crate) fn number_size(value: &HezenValue) -> usize {
+    match value {
+        HezenValue::BigInt(i) => i.bits().div_ceil(8) as usize,
+        HezenValue::Decimal(_) => size_of::<Decimal>(),
+        _ => 0,
+    }
+}
+
+/// The approximate size of `value` and everything it contains, each shared
+/// collection counted once. Used for values made by natives, which the
+/// interpreter does not see being built.
+pub(crate) fn deep_size(value: &HezenValue) -> usize {
+    fn walk(value: &HezenValue, seen: &mut HashSet<usize>) -> usize {
+        let children: Vec<HezenValue> = match value {
+            HezenValue::Tuple(values) => {
+                if !seen.insert(Rc::as_ptr(values) as *const () as usize) {
+                    return 0;
+                }
+                values.to_vec()
+            }
+            HezenValue::List(values) => {
+                if !seen.insert(Rc::as_ptr(values) as *const () as usize) {
+                    return 0;
+                }
+                values.borrow().clone()
+            }
+            HezenValue::Map(entries) => {
+                if !seen.insert(Rc::as_ptr(entries) as *const () as usize) {
+                    return 0;
+                }
+                entries.borrow().values().cloned().collect()
+            }
+            _ => Vec::new(),
+        };
+
+        shallow_size(value)
+            + children
+                .iter()
+                .map(|child| walk(child, seen).saturating_sub(size_of::<HezenValue>()))
+                .sum::<usize>()
+    }
+
+    walk(value, &mut HashSet::new())
+}
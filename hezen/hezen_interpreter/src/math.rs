@@ -0,0 +1,193 @@
+use num_traits::{Signed, ToPrimitive};
+use rust_decimal::Decimal;
+
+use hezen_core::error::HezenError;
+
+use crate::{
+    environment::HezenValue,
+    module::{native_error, HezenModule},
+    numeric::{self, ArithmeticOp, NumericError},
+    token::Token,
+};
+
+/// The `math` namespace.
+pub fn module() -> HezenModule {
+    HezenModule::new("math")
+        .constant("pi", HezenValue::Number(std::f64::consts::PI))
+        .constant("e", HezenValue::Number(std::f64::consts::E))
+        .constant("inf", HezenValue::Number(f64::INFINITY))
+        .constant("nan", HezenValue::Number(f64::NAN))
+        .function("sqrt", 1, |paren, args| {
+            float(paren, "sqrt", args, f64::sqrt)
+        })
+        .function("pow", 2, |paren, args| {
+            numeric::arithmetic(ArithmeticOp::Pow, &args[0], &args[1])
+                .map_err(|why| arithmetic_error(paren, "pow", why, args))
+        })
+        .function("abs", 1, |paren, args| abs(paren, &args[0]))
+        .function("floor", 1, |paren, args| {
+            rounding(paren, "floor", &args[0], f64::floor, Decimal::floor)
+        })
+        .function("ceil", 1, |paren, args| {
+            rounding(paren, "ceil", &args[0], f64::ceil, Decimal::ceil)
+        })
+        .function("round", 1, |paren, args| {
+            rounding(paren, "round", &args[0], f64::round, |d| {
+                d.round_dp_with_strategy(0, rust_decimal::RoundingStrategy::MidpointAwayFromZero)
+            })
+        })
+        .function("min", 2, |paren, args| {
+            extremum(paren, "min", args, std::cmp::Ordering::Less)
+        })
+        .function("max", 2, |paren, args| {
+            extremum(paren, "max", args, std::cmp::Ordering::Greater)
+        })
+        .function("sin", 1, |paren, args| float(paren, "sin", args, f64::sin))
+        .function("cos", 1, |paren, args| float(paren, "cos", args, f64::cos))
+        .function("tan", 1, |paren, args| float(paren, "tan", args, f64::tan))
+        .function("asin", 1, |paren, args| {
+            float(paren, "asin", args, f64::asin)
+        })
+        .function("acos", 1, |paren, args| {
+            float(paren, "acos", args, f64::acos)
+        })
+        .function("atan", 1, |paren, args| {
+            float(paren, "atan", args, f64::atan)
+        })
+        .function("atan2", 2, |paren, args| {
+            let y = number(paren, "atan2", args, 0)?;
+            let x = number(paren, "atan2", args, 1)?;
+            Ok(HezenValue::Number(y.atan2(x)))
+        })
+        .function("exp", 1, |paren, args| float(paren, "exp", args, f64::exp))
+        .function("log", 1, |paren, args| float(paren, "log", args, f64::ln))
+        .function("log2", 1, |paren, args| {
+            float(paren, "log2", args, f64::log2)
+        })
+        .function("log10", 1, |paren, args| {
+            float(paren, "log10", args, f64::log10)
+        })
+        .function("is_nan", 1, |paren, args| {
+            Ok(HezenValue::Bool(number(paren, "is_nan", args, 0)?.is_nan()))
+        })
+        .function("is_finite", 1, |paren, args| {
+            Ok(HezenValue::Bool(
+                number(paren, "is_finite", args, 0)?.is_finite(),
+            ))
+        })
+}
+
+fn number(
+    paren: &Token,
+    function: &str,
+    args: &[HezenValue],
+    index: usize,
+) -> Result<f64, HezenError> {
+    numeric::to_f64(&args[index]).ok_or_else(|| {
+        native_error(
+            paren,
+            format!(
+                "Expected argument {} of 'math.{}' to be a number, not '{}'",
+                index + 1,
+                function,
+                args[index].type_name()
+            ),
+        )
+    })
+}
+
+fn float(
+    paren: &Token,
+    function: &str,
+    args: &[HezenValue],
+    operation: fn(f64) -> f64,
+) -> Result<HezenValue, HezenError> {
+    Ok(HezenValue::Number(operation(number(
+        paren, function, args, 0,
+    )?)))
+}
+
+fn arithmetic_error(
+    paren: &Token,
+    function: &str,
+    why: NumericError,
+    args: &[HezenValue],
+) -> HezenError {
+    native_error(
+        paren,
+        match why {
+            NumericError::NotNumbers => format!(
+                "Arguments of 'math.{}' must be two numbers, not '{}' and '{}'",
+                function,
+                args[0].type_name(),
+                args[1].type_name()
+            ),
+            NumericError::DivisionByZero => "Division by zero".to_string(),
+            NumericError::Overflow => format!("Numeric overflow in 'math.{function}'"),
+        },
+    )
+}
+
+fn abs(paren: &Token, value: &HezenValue) -> Result<HezenValue, HezenError> {
+    match value {
+        HezenValue::Integer(i) => Ok(match i.checked_abs() {
+            Some(i) => HezenValue::Integer(i),
+            None => HezenValue::BigInt(num_bigint::BigInt::from(*i).abs()),
+        }),
+        HezenValue::BigInt(b) => Ok(numeric::normalize_bigint(b.abs())),
+        HezenValue::Decimal(d) => Ok(HezenValue::Decimal(d.abs())),
+        HezenValue::Number(n) => Ok(HezenValue::Number(n.abs())),
+        _ => Err(native_error(
+            paren,
+            format!(
+                "Expected argument 1 of 'math.abs' to be a number, not '{}'",
+                value.type_name()
+            ),
+        )),
+    }
+}
+
+/// Rounds to a whole number. Integers are already whole; decimals stay exact;
+/// floats become integers when they fit.
+fn rounding(
+    paren: &Token,
+    function: &str,
+    value: &HezenValue,
+    float: fn(f64) -> f64,
+    decimal: fn(&Decimal) -> Decimal,
+) -> Result<HezenValue, HezenError> {
+    match value {
+        HezenValue::Integer(_) | HezenValue::BigInt(_) => Ok(value.clone()),
+        HezenValue::Decimal(d) => Ok(HezenValue::Decimal(decimal(d))),
+        HezenValue::Number(n) => {
+            let rounded = float(*n);
+
+            Ok(match rounded.to_i64() {
+                Some(i) => HezenValue::Integer(i),
+                None => HezenValue::Number(rounded),
+            })
+        }
+        _ => Err(native_error(
+            paren,
+            format!(
+                "Expected argument 1 of 'math.{}' to be a number, not '{}'",
+                function,
+                value.type_name()
+            ),
+        )),
+    }
+}
+
+fn extremum(
+    paren: &Token,
+    function: &str,
+    args: &[HezenValue],
+    wanted: std::cmp::Ordering,
+) -> Result<HezenValue, HezenError> {
+    match numeric::compare(&args[0], &args[1]) {
+        Ok(Some(ordering)) if ordering == wanted => Ok(args[0].clone()),
+        Ok(Some(_)) => Ok(args[1].clone()),
+        Ok(None) => Ok(HezenValue::Number(f64::NAN)),
+        Err(why) => Err(arithmetic_error(paren, function, why, args)),
+    }
+}
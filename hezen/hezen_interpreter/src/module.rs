@@ -0,0 +1,88 @@
+use std::{collections::HashMap, fmt::Display, rc::Rc};
+
+use hezen_core::error::HezenError;
+
+use crate::{
+    environment::HezenValue, function::HezenNativeFunction, interpreter::Interpreter, token::Token,
+};
+
+/// A built-in namespace such as `math`, whose members are reached with `.`.
+#[derive(Debug, Clone)]
+pub struct HezenModule {
+    pub name: String,
+    members: HashMap<String, HezenValue>,
+}
+
+impl HezenModule {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            members: HashMap::new(),
+        }
+    }
+
+    pub fn constant(mut self, name: &str, value: HezenValue) -> Self {
+        self.members.insert(name.to_string(), value);
+        self
+    }
+
+    pub fn function(
+        self,
+        name: &str,
+        arity: usize,
+        function: fn(&Token, &[HezenValue]) -> Result<HezenValue, HezenError>,
+    ) -> Self {
+        self.function_with_interpreter(name, arity, move |_, paren, arguments| {
+            function(paren, arguments)
+        })
+    }
+
+    /// Like [`function`](Self::function), for functions that need the
+    /// interpreter.
+    pub fn function_with_interpreter(
+        mut self,
+        name: &str,
+        arity: usize,
+        function: impl Fn(&mut Interpreter, &Token, &[HezenValue]) -> Result<HezenValue, HezenError>
+            + 'static,
+    ) -> Self {
+        let function = HezenNativeFunction::with_interpreter(
+            &format!("{}.{}", self.name, name),
+            arity,
+            function,
+        );
+
+        self.members.insert(
+            name.to_string(),
+            HezenValue::NativeFunction(Rc::new(function)),
+        );
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<HezenValue> {
+        self.members.get(name).cloned()
+    }
+}
+
+impl PartialEq for HezenModule {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Display for HezenModule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<module {}>", self.name)
+    }
+}
+
+/// Builds an error for a native function, reported at `paren`, the closing
+/// parenthesis of its call.
+pub fn native_error(paren: &Token, message: String) -> HezenError {
+    native_error_with_kind(paren, "RuntimeError", message)
+}
+
+/// Like [`native_error`], but with an error kind other than `RuntimeError`.
+pub fn native_error_with_kind(paren: &Token, kind: &str, message: String) -> HezenError {
+    HezenError::runtime_with_kind(kind, paren.position.clone(), message)
+}
@@ -0,0 +1,290 @@
+use std::cmp::Ordering;
+
+use num_bigint::BigInt;
+use num_traits::{One, ToPrimitive, Zero};
+use rust_decimal::Decimal;
+
+use crate::environment::HezenValue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+#[derive(Debug)]
+pub enum NumericError {
+    NotNumbers,
+    DivisionByZero,
+    Overflow,
+}
+
+/// A numeric value lifted to a common representation, ordered by how much
+/// precision it can hold. Mixing two kinds promotes both to the wider one,
+/// except that floats always win: once a float is involved exactness is lost.
+enum Promoted {
+    Integer(i64, i64),
+    BigInt(BigInt, BigInt),
+    Decimal(Decimal, Decimal),
+    Float(f64, f64),
+}
+
+fn rank(value: &HezenValue) -> Option<u8> {
+    match value {
+        HezenValue::Integer(_) => Some(0),
+        HezenValue::BigInt(_) => Some(1),
+        HezenValue::Decimal(_) => Some(2),
+        HezenValue::Number(_) => Some(3),
+        _ => None,
+    }
+}
+
+fn to_bigint(value: &HezenValue) -> BigInt {
+    match value {
+        HezenValue::Integer(i) => BigInt::from(*i),
+        HezenValue::BigInt(b) => b.clone(),
+        _ => unreachable!(),
+    }
+}
+
+fn to_decimal(value: &HezenValue) -> Option<Decimal> {
+    match value {
+        HezenValue::Integer(i) => Some(Decimal::from(*i)),
+        HezenValue::BigInt(b) => b
+            .to_i128()
+            .and_then(|i| Decimal::try_from_i128_with_scale(i, 0).ok()),
+        HezenValue::Decimal(d) => Some(*d),
+        _ => unreachable!(),
+    }
+}
+
+pub fn to_f64(value: &HezenValue) -> Option<f64> {
+    match value {
+        HezenValue::Integer(i) => Some(*i as f64),
+        HezenValue::BigInt(b) => b.to_f64(),
+        HezenValue::Decimal(d) => d.to_f64(),
+        HezenValue::Number(n) => Some(*n),
+        _ => None,
+    }
+}
+
+fn promote(left: &HezenValue, right: &HezenValue) -> Result<Promoted, NumericError> {
+    let rank = rank(left)
+        .zip(rank(right))
+        .map(|(l, r)| l.max(r))
+        .ok_or(NumericError::NotNumbers)?;
+
+    Ok(match rank {
+        0 => match (left, right) {
+            (HezenValue::Integer(l), HezenValue::Integer(r)) => Promoted::Integer(*l, *r),
+            _ => unreachable!(),
+        },
+        1 => Promoted::BigInt(to_bigint(left), to_bigint(right)),
+        2 => Promoted::Decimal(
+            to_decimal(left).ok_or(NumericError::Overflow)?,
+            to_decimal(right).ok_or(NumericError::Overflow)?,
+        ),
+        _ => Promoted::Float(to_f64(left).unwrap(), to_f64(right).unwrap()),
+    })
+}
+
+/// Turns an arbitrary-precision result back into a plain integer when it fits,
+/// so that equal values always share one representation.
+pub fn normalize_bigint(value: BigInt) -> HezenValue {
+    match value.to_i64() {
+        Some(i) => HezenValue::Integer(i),
+        None => HezenValue::BigInt(value),
+    }
+}
+
+pub fn is_number(value: &HezenValue) -> bool {
+    rank(value).is_some()
+}
+
+pub fn arithmetic(
+    op: ArithmeticOp,
+    left: &HezenValue,
+    right: &HezenValue,
+) -> Result<HezenValue, NumericError> {
+    if op == ArithmeticOp::Pow {
+        return power(left, right);
+    }
+
+    match promote(left, right)? {
+        Promoted::Integer(l, r) => {
+            if r == 0 && matches!(op, ArithmeticOp::Div | ArithmeticOp::Rem) {
+                return Err(NumericError::DivisionByZero);
+            }
+
+            let result = match op {
+                ArithmeticOp::Add => l.checked_add(r),
+                ArithmeticOp::Sub => l.checked_sub(r),
+                ArithmeticOp::Mul => l.checked_mul(r),
+                ArithmeticOp::Div => {
+                    if l.checked_rem(r) == Some(0) {
+                        l.checked_div(r)
+                    } else {
+                        return Ok(HezenValue::Number(l as f64 / r as f64));
+                    }
+                }
+                ArithmeticOp::Rem => l.checked_rem(r),
+                ArithmeticOp::Pow => unreachable!(),
+            };
+
+            match result {
+                Some(i) => Ok(HezenValue::Integer(i)),
+                None => arithmetic(
+                    op,
+                    &HezenValue::BigInt(BigInt::from(l)),
+                    &HezenValue::BigInt(BigInt::from(r)),
+                ),
+            }
+        }
+        Promoted::BigInt(l, r) => Ok(match op {
+            ArithmeticOp::Div | ArithmeticOp::Rem if r.is_zero() => {
+                return Err(NumericError::DivisionByZero)
+            }
+            ArithmeticOp::Add => normalize_bigint(l + r),
+            ArithmeticOp::Sub => normalize_bigint(l - r),
+            ArithmeticOp::Mul => normalize_bigint(l * r),
+            ArithmeticOp::Div => {
+                if (&l % &r).is_zero() {
+                    normalize_bigint(l / r)
+                } else {
+                    HezenValue::Number(
+                        l.to_f64().unwrap_or(f64::NAN) / r.to_f64().unwrap_or(f64::NAN),
+                    )
+                }
+            }
+            ArithmeticOp::Rem => normalize_bigint(l % r),
+            ArithmeticOp::Pow => unreachable!(),
+        }),
+        Promoted::Decimal(l, r) => {
+            let result = match op {
+                ArithmeticOp::Add => l.checked_add(r),
+                ArithmeticOp::Sub => l.checked_sub(r),
+                ArithmeticOp::Mul => l.checked_mul(r),
+                ArithmeticOp::Div | ArithmeticOp::Rem if r.is_zero() => {
+                    return Err(NumericError::DivisionByZero)
+                }
+                ArithmeticOp::Div => l.checked_div(r),
+                ArithmeticOp::Rem => l.checked_rem(r),
+                ArithmeticOp::Pow => unreachable!(),
+            };
+
+            result
+                .map(HezenValue::Decimal)
+                .ok_or(NumericError::Overflow)
+        }
+        Promoted::Float(l, r) => Ok(HezenValue::Number(match op {
+            ArithmeticOp::Add => l + r,
+            ArithmeticOp::Sub => l - r,
+            ArithmeticOp::Mul => l * r,
+            ArithmeticOp::Div => l / r,
+            ArithmeticOp::Rem => l % r,
+            ArithmeticOp::Pow => l.powf(r),
+        })),
+    }
+}
+
+/// The most bits an exact power may have. Computing one happens in a single
+/// step that no limit can interrupt, so larger results overflow instead.
+const MAX_POWER_BITS: u64 = 1 << 22;
+
+/// Raises `base` to `exponent`. Whole exponents keep integers, bigints and
+/// decimals exact; anything else falls back to floating point.
+fn power(base: &HezenValue, exponent: &HezenValue) -> Result<HezenValue, NumericError> {
+    if !is_number(base) || !is_number(exponent) {
+        return Err(NumericError::NotNumbers);
+    }
+
+    let exponent_int = match exponent {
+        HezenValue::Integer(i) => u32::try_from(*i).ok(),
+        _ => None,
+    };
+
+    match (base, exponent_int) {
+        (HezenValue::Integer(b), Some(e)) => match b.checked_pow(e) {
+            Some(i) => Ok(HezenValue::Integer(i)),
+            None => bigint_power(&BigInt::from(*b), e),
+        },
+        (HezenValue::BigInt(b), Some(e)) => bigint_power(b, e),
+        (HezenValue::Decimal(b), Some(e)) => decimal_power(*b, e).map(HezenValue::Decimal),
+        _ => Ok(HezenValue::Number(
+            to_f64(base)
+                .unwrap_or(f64::NAN)
+                .powf(to_f64(exponent).unwrap_or(f64::NAN)),
+        )),
+    }
+}
+
+/// Fails instead of computing a power with more than [`MAX_POWER_BITS`] bits.
+fn bigint_power(base: &BigInt, exponent: u32) -> Result<HezenValue, NumericError> {
+    // The powers of 0, 1 and -1 stay small whatever the exponent.
+    let bits = if base.magnitude().is_one() || base.is_zero() {
+        0
+    } else {
+        base.bits()
+    };
+
+    if bits.saturating_mul(u64::from(exponent)) > MAX_POWER_BITS {
+        return Err(NumericError::Overflow);
+    }
+
+    Ok(normalize_bigint(base.pow(exponent)))
+}
+
+/// Exponentiation by squaring, failing as soon as a step overflows.
+fn decimal_power(mut base: Decimal, mut exponent: u32) -> Result<Decimal, NumericError> {
+    let mut result = Decimal::ONE;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.checked_mul(base).ok_or(NumericError::Overflow)?;
+        }
+
+        exponent >>= 1;
+
+        if exponent > 0 {
+            base = base.checked_mul(base).ok_or(NumericError::Overflow)?;
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn compare(left: &HezenValue, right: &HezenValue) -> Result<Option<Ordering>, NumericError> {
+    Ok(match promote(left, right)? {
+        Promoted::Integer(l, r) => Some(l.cmp(&r)),
+        Promoted::BigInt(l, r) => Some(l.cmp(&r)),
+        Promoted::Decimal(l, r) => Some(l.cmp(&r)),
+        Promoted::Float(l, r) => l.partial_cmp(&r),
+    })
+}
+
+pub fn negate(value: &HezenValue) -> Result<HezenValue, NumericError> {
+    match value {
+        HezenValue::Integer(i) => Ok(match i.checked_neg() {
+            Some(i) => HezenValue::Integer(i),
+            None => HezenValue::BigInt(-BigInt::from(*i)),
+        }),
+        HezenValue::BigInt(b) => Ok(normalize_bigint(-b)),
+        HezenValue::Decimal(d) => Ok(HezenValue::Decimal(-*d)),
+        HezenValue::Number(n) => Ok(HezenValue::Number(-n)),
+        _ => Err(NumericError::NotNumbers),
+    }
+}
+
+pub fn is_zero(value: &HezenValue) -> bool {
+    match value {
+        HezenValue::Integer(i) => *i == 0,
+        HezenValue::BigInt(b) => b.is_zero(),
+        HezenValue::Decimal(d) => d.is_zero(),
+        HezenValue::Number(n) => *n == 0.0,
+        _ => false,
+    }
+}
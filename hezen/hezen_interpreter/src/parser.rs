@@ -1,9 +1,10 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
-use hezen_core::error::{HezenError, HezenErrorList};
-use crate::ast::{Expr, Literal, Stmt};
+use crate::ast::{Destructure, Expr, Literal, MatchArm, Pattern, Stmt};
 use crate::token::{Token, TokenType, Tokens};
+use hezen_core::error::{HezenError, HezenErrorList};
 
 macro_rules! match_literal_token {
     ($self:ident, $ty:path) => {
@@ -30,14 +31,33 @@ type ParseResult<T> = Result<T, ParseError>;
 pub struct Parser<'a> {
     tokens: Tokens,
     current: usize,
+    /// Doc comments keyed by the index of the token they precede.
+    docs: HashMap<usize, String>,
     errors: &'a mut HezenErrorList,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(tokens: Tokens, errors: &'a mut HezenErrorList) -> Self {
+        let mut code = Tokens::new();
+        let mut docs: HashMap<usize, String> = HashMap::new();
+
+        for token in tokens.list {
+            if let TokenType::DocComment(text) = token.ty {
+                docs.entry(code.list.len())
+                    .and_modify(|doc| {
+                        doc.push('\n');
+                        doc.push_str(&text);
+                    })
+                    .or_insert(text);
+            } else {
+                code.add(token);
+            }
+        }
+
         Self {
-            tokens,
+            tokens: code,
             current: 0,
+            docs,
             errors,
         }
     }
@@ -56,14 +76,25 @@ impl<'a> Parser<'a> {
 
     fn declaration(&mut self) -> Option<Stmt> {
         if self.match_token(TokenType::Class) {
-            match self.class_declaration() {
+            let doc = self.docs.remove(&(self.current - 1));
+
+            match self.class_declaration(doc) {
+                Ok(stmt) => return Some(stmt),
+                Err(_) => self.synchronize(),
+            }
+        }
+
+        if self.match_token(TokenType::Enum) {
+            match self.enum_declaration() {
                 Ok(stmt) => return Some(stmt),
                 Err(_) => self.synchronize(),
             }
         }
 
         if self.match_token(TokenType::Fn) {
-            match self.function_declaration("function") {
+            let doc = self.docs.remove(&(self.current - 1));
+
+            match self.function_declaration("function", doc) {
                 Ok(stmt) => return Some(stmt),
                 Err(_) => self.synchronize(),
             }
@@ -88,6 +119,24 @@ impl<'a> Parser<'a> {
     fn var_declaration(&mut self) -> ParseResult<Stmt> {
         let mutable = self.match_token(TokenType::Mut);
 
+        if self.check(TokenType::LeftParen) || self.check(TokenType::LeftBracket) {
+            let target = self.destructure_target()?;
+
+            self.consume(
+                TokenType::Equal,
+                "Expected '=' after destructuring pattern.",
+            )?;
+
+            let value = self.expression()?;
+
+            self.consume(
+                TokenType::Semicolon,
+                "Expected ';' after variable declaration.",
+            )?;
+
+            return Ok(Stmt::Destructure(target, value, mutable));
+        }
+
         let name = self.consume(TokenType::Identifier, "Expected variable name.")?;
 
         let initializer = if self.match_token(TokenType::Equal) {
@@ -108,7 +157,48 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn function_declaration(&mut self, kind: &str) -> ParseResult<Stmt> {
+    fn destructure_target(&mut self) -> ParseResult<Destructure> {
+        if self.match_token(TokenType::LeftParen) || self.match_token(TokenType::LeftBracket) {
+            let open = self.previous();
+            let (close, message) = if open.ty == TokenType::LeftParen {
+                (TokenType::RightParen, "Expected ')' after tuple pattern.")
+            } else {
+                (TokenType::RightBracket, "Expected ']' after list pattern.")
+            };
+
+            let mut targets = Vec::new();
+            let mut rest = None;
+
+            if !self.check(close.clone()) {
+                loop {
+                    if self.match_token(TokenType::DotDotDot) {
+                        rest = Some(self.consume(
+                            TokenType::Identifier,
+                            "Expected variable name after '...'.",
+                        )?);
+                        break;
+                    }
+
+                    targets.push(self.destructure_target()?);
+
+                    if !self.match_token(TokenType::Comma) {
+                        break;
+                    }
+                }
+            }
+
+            self.consume(close, message)?;
+
+            return Ok(Destructure::Sequence(open, targets, rest));
+        }
+
+        Ok(Destructure::Name(self.consume(
+            TokenType::Identifier,
+            "Expected variable name.",
+        )?))
+    }
+
+    fn function_declaration(&mut self, kind: &str, doc: Option<String>) -> ParseResult<Stmt> {
         let name = self.consume(TokenType::Identifier, &format!("Expected {kind} name."))?;
 
         self.consume(
@@ -117,6 +207,7 @@ impl<'a> Parser<'a> {
         )?;
 
         let mut parameters = Vec::new();
+        let mut destructures = Vec::new();
 
         if !self.check(TokenType::RightParen) {
             loop {
@@ -124,7 +215,27 @@ impl<'a> Parser<'a> {
                     return Err(self.error(self.peek(), "Cannot have more than 255 parameters."));
                 }
 
-                parameters.push(self.consume(TokenType::Identifier, "Expected parameter name.")?);
+                if self.check(TokenType::LeftParen) || self.check(TokenType::LeftBracket) {
+                    // Destructured parameters are passed in under a name no identifier can
+                    // collide with, then taken apart at the start of the body.
+                    let position = self.peek().position;
+                    let target = self.destructure_target()?;
+                    let parameter = Token::new(
+                        TokenType::Identifier,
+                        format!("#{}", parameters.len()),
+                        position,
+                    );
+
+                    destructures.push(Stmt::Destructure(
+                        target,
+                        Expr::Variable(parameter.clone()),
+                        false,
+                    ));
+                    parameters.push(parameter);
+                } else {
+                    parameters
+                        .push(self.consume(TokenType::Identifier, "Expected parameter name.")?);
+                }
 
                 if !self.match_token(TokenType::Comma) {
                     break;
@@ -134,12 +245,19 @@ impl<'a> Parser<'a> {
 
         self.consume(TokenType::RightParen, "Expected ')' after parameters.")?;
 
-        let body = self.block_statement()?;
+        let mut body = self.block_statement()?;
+
+        if !destructures.is_empty() {
+            if let Stmt::Block(statements) = body {
+                destructures.extend(statements);
+                body = Stmt::Block(destructures);
+            }
+        }
 
-        Ok(Stmt::Function(name, parameters, Box::new(body)))
+        Ok(Stmt::Function(name, parameters, Box::new(body), doc))
     }
 
-    fn class_declaration(&mut self) -> ParseResult<Stmt> {
+    fn class_declaration(&mut self, doc: Option<String>) -> ParseResult<Stmt> {
         let name = self.consume(TokenType::Identifier, "Expected class name.")?;
 
         let superclass = if self.match_token(TokenType::Less) {
@@ -156,12 +274,51 @@ impl<'a> Parser<'a> {
         let mut methods = Vec::new();
 
         while !self.check(TokenType::RightBrace) && !self.is_at_end() {
-            methods.push(self.function_declaration("method")?);
+            let method_doc = self.docs.remove(&self.current);
+            methods.push(self.function_declaration("method", method_doc)?);
         }
 
         self.consume(TokenType::RightBrace, "Expected '}' after class body.")?;
 
-        Ok(Stmt::Class(name, superclass, methods))
+        Ok(Stmt::Class(name, superclass, methods, doc))
+    }
+
+    fn enum_declaration(&mut self) -> ParseResult<Stmt> {
+        let name = self.consume(TokenType::Identifier, "Expected enum name.")?;
+
+        self.consume(TokenType::LeftBrace, "Expected '{' before enum body.")?;
+
+        let mut variants = Vec::new();
+
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let variant = self.consume(TokenType::Identifier, "Expected variant name.")?;
+
+            let mut fields = Vec::new();
+
+            if self.match_token(TokenType::LeftParen) {
+                if !self.check(TokenType::RightParen) {
+                    loop {
+                        fields.push(self.consume(TokenType::Identifier, "Expected field name.")?);
+
+                        if !self.match_token(TokenType::Comma) {
+                            break;
+                        }
+                    }
+                }
+
+                self.consume(TokenType::RightParen, "Expected ')' after variant fields.")?;
+            }
+
+            variants.push((variant, fields));
+
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expected '}' after enum body.")?;
+
+        Ok(Stmt::Enum(name, variants))
     }
 
     fn statement(&mut self) -> ParseResult<Stmt> {
@@ -177,8 +334,12 @@ impl<'a> Parser<'a> {
             return self.if_statement();
         } else if self.match_token(TokenType::While) {
             return self.while_statement();
+        } else if self.match_token(TokenType::Try) {
+            return self.try_statement();
         } else if self.check(TokenType::LeftBrace) {
             return self.block_statement();
+        } else if self.check(TokenType::Match) {
+            return self.match_statement();
         }
 
         self.expression_statement()
@@ -192,6 +353,14 @@ impl<'a> Parser<'a> {
         Ok(Stmt::Expression(expr))
     }
 
+    fn match_statement(&mut self) -> ParseResult<Stmt> {
+        let expr = self.expression()?;
+
+        self.match_token(TokenType::Semicolon);
+
+        Ok(Stmt::Expression(expr))
+    }
+
     fn block_statement(&mut self) -> ParseResult<Stmt> {
         let mut statements = Vec::new();
 
@@ -208,6 +377,22 @@ impl<'a> Parser<'a> {
         Ok(Stmt::Block(statements))
     }
 
+    fn try_statement(&mut self) -> ParseResult<Stmt> {
+        let body = self.block_statement()?;
+
+        self.consume(TokenType::Catch, "Expected 'catch' after try block.")?;
+
+        let name = if self.check(TokenType::Identifier) {
+            Some(self.advance())
+        } else {
+            None
+        };
+
+        let handler = self.block_statement()?;
+
+        Ok(Stmt::Try(Box::new(body), name, Box::new(handler)))
+    }
+
     fn if_statement(&mut self) -> ParseResult<Stmt> {
         let condition = self.expression()?;
 
@@ -227,14 +412,16 @@ impl<'a> Parser<'a> {
     }
 
     fn while_statement(&mut self) -> ParseResult<Stmt> {
+        let keyword = self.previous();
         let condition = self.expression()?;
 
         let then = self.block_statement()?;
 
-        Ok(Stmt::While(condition, Box::new(then)))
+        Ok(Stmt::While(keyword, condition, Box::new(then)))
     }
 
     fn for_statement(&mut self) -> ParseResult<Stmt> {
+        let keyword = self.previous();
         self.consume(TokenType::LeftParen, "Expected '(' after 'for'.")?;
 
         let initializer = if self.match_token(TokenType::Semicolon) {
@@ -268,9 +455,9 @@ impl<'a> Parser<'a> {
         }
 
         if let Some(condition) = condition {
-            body = Stmt::While(condition, Box::new(body));
+            body = Stmt::While(keyword, condition, Box::new(body));
         } else {
-            body = Stmt::While(Expr::Literal(Literal::Bool(true)), Box::new(body));
+            body = Stmt::While(keyword, Expr::Literal(Literal::Bool(true)), Box::new(body));
         }
 
         if let Some(initializer) = initializer {
@@ -300,7 +487,19 @@ impl<'a> Parser<'a> {
         let keyword = self.previous();
 
         let value = if !self.check(TokenType::Semicolon) {
-            Some(self.expression()?)
+            let value = self.expression()?;
+
+            if self.check(TokenType::Comma) {
+                let mut values = vec![value];
+
+                while self.match_token(TokenType::Comma) {
+                    values.push(self.expression()?);
+                }
+
+                Some(Expr::Tuple(keyword.clone(), values))
+            } else {
+                Some(value)
+            }
         } else {
             None
         };
@@ -315,7 +514,7 @@ impl<'a> Parser<'a> {
     }
 
     fn assignment(&mut self) -> ParseResult<Expr> {
-        let expr = self.or()?;
+        let expr = self.conditional()?;
 
         if self.match_token(TokenType::Equal) {
             let equals = self.previous();
@@ -324,12 +523,83 @@ impl<'a> Parser<'a> {
             match expr {
                 Expr::Variable(name) => return Ok(Expr::Assign(name, Box::new(value))),
                 Expr::Get(object, name) => return Ok(Expr::Set(object, name, Box::new(value))),
+                Expr::Index(object, bracket, index) => {
+                    return Ok(Expr::SetIndex(object, bracket, index, Box::new(value)))
+                }
                 _ => {}
             }
 
             return Err(self.error(equals, "Invalid assignment target."));
         }
 
+        if self.match_token(TokenType::PlusEqual)
+            || self.match_token(TokenType::MinusEqual)
+            || self.match_token(TokenType::StarEqual)
+            || self.match_token(TokenType::SlashEqual)
+            || self.match_token(TokenType::PercentEqual)
+        {
+            let operator = self.previous();
+            let value = self.assignment()?;
+
+            return self.compound_assignment(expr, operator, value);
+        }
+
+        Ok(expr)
+    }
+
+    fn compound_assignment(
+        &mut self,
+        target: Expr,
+        operator: Token,
+        value: Expr,
+    ) -> ParseResult<Expr> {
+        match target {
+            Expr::Variable(name) => Ok(Expr::CompoundAssign(name, operator, Box::new(value))),
+            Expr::Get(object, name) => {
+                Ok(Expr::CompoundSet(object, name, operator, Box::new(value)))
+            }
+            Expr::Index(object, bracket, index) => Ok(Expr::CompoundSetIndex(
+                object,
+                bracket,
+                index,
+                operator,
+                Box::new(value),
+            )),
+            _ => Err(self.error(operator, "Invalid assignment target.")),
+        }
+    }
+
+    fn conditional(&mut self) -> ParseResult<Expr> {
+        let expr = self.coalesce()?;
+
+        if self.match_token(TokenType::Question) {
+            let then_expr = self.expression()?;
+            self.consume(
+                TokenType::Colon,
+                "Expected ':' after then branch of conditional expression.",
+            )?;
+            let else_expr = self.conditional()?;
+
+            return Ok(Expr::Conditional(
+                Box::new(expr),
+                Box::new(then_expr),
+                Box::new(else_expr),
+            ));
+        }
+
+        Ok(expr)
+    }
+
+    fn coalesce(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.or()?;
+
+        while self.match_token(TokenType::QuestionQuestion) {
+            let operator = self.previous();
+            let right = self.or()?;
+
+            expr = Expr::Logical(Box::new(expr), operator, Box::new(right));
+        }
+
         Ok(expr)
     }
 
@@ -405,7 +675,10 @@ impl<'a> Parser<'a> {
     fn factor(&mut self) -> ParseResult<Expr> {
         let mut expr = self.unary()?;
 
-        while self.match_token(TokenType::Star) || self.match_token(TokenType::Slash) {
+        while self.match_token(TokenType::Star)
+            || self.match_token(TokenType::Slash)
+            || self.match_token(TokenType::Percent)
+        {
             let operator = self.previous();
             let right = self.unary()?;
 
@@ -430,7 +703,99 @@ impl<'a> Parser<'a> {
             return Ok(Expr::Unary(operator, Box::new(right)));
         }
 
-        self.call()
+        self.power()
+    }
+
+    fn power(&mut self) -> ParseResult<Expr> {
+        let expr = self.postfix()?;
+
+        if self.match_token(TokenType::StarStar) {
+            let operator = self.previous();
+            let right = self.unary()?;
+
+            return Ok(Expr::Binary(Box::new(expr), operator, Box::new(right)));
+        }
+
+        Ok(expr)
+    }
+
+    fn postfix(&mut self) -> ParseResult<Expr> {
+        let expr = self.call()?;
+
+        // `++` and `--` are two `+` or `-` tokens written together. They only
+        // increment an assignable target that is not directly followed by
+        // another operand, so that `5--3` and `y--2` still subtract a
+        // negative number.
+        let assignable = matches!(expr, Expr::Variable(_) | Expr::Get(..) | Expr::Index(..));
+
+        if assignable && self.doubled() && !self.operand_follows() {
+            let first = self.advance();
+            let second = self.advance();
+            let (ty, lexeme) = match first.ty {
+                TokenType::Plus => (TokenType::PlusPlus, "++"),
+                _ => (TokenType::MinusMinus, "--"),
+            };
+            let position = second
+                .position
+                .starting_at(first.position.start_line, first.position.start_column);
+            let operator = Token::new(ty, lexeme.to_string(), position);
+
+            let assignment =
+                self.compound_assignment(expr, operator, Expr::Literal(Literal::Integer(1)))?;
+
+            return Ok(Expr::Postfix(Box::new(assignment)));
+        }
+
+        Ok(expr)
+    }
+
+    /// Whether the current token and the next are `++` or `--`.
+    fn doubled(&self) -> bool {
+        match (
+            self.tokens.list.get(self.current),
+            self.tokens.list.get(self.current + 1),
+        ) {
+            (Some(first), Some(second)) => {
+                matches!(first.ty, TokenType::Plus | TokenType::Minus)
+                    && first.ty == second.ty
+                    && adjacent(first, second)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether an operand starts right after the `++` or `--` at the current
+    /// token.
+    fn operand_follows(&self) -> bool {
+        match (
+            self.tokens.list.get(self.current + 1),
+            self.tokens.list.get(self.current + 2),
+        ) {
+            (Some(second), Some(token)) => {
+                adjacent(second, token)
+                    && matches!(
+                        token.ty,
+                        TokenType::Identifier
+                            | TokenType::String(_)
+                            | TokenType::Interpolation(_)
+                            | TokenType::Number(_)
+                            | TokenType::Integer(_)
+                            | TokenType::BigInt(_)
+                            | TokenType::Decimal(_)
+                            | TokenType::LeftParen
+                            | TokenType::LeftBracket
+                            | TokenType::Minus
+                            | TokenType::Bang
+                            | TokenType::True
+                            | TokenType::False
+                            | TokenType::Nil
+                            | TokenType::Self_
+                            | TokenType::Super
+                            | TokenType::Match
+                    )
+            }
+            _ => false,
+        }
     }
 
     fn call(&mut self) -> ParseResult<Expr> {
@@ -443,6 +808,15 @@ impl<'a> Parser<'a> {
                 let name =
                     self.consume(TokenType::Identifier, "Expected property name after '.'.")?;
                 expr = Expr::Get(Box::new(expr), name);
+            } else if self.match_token(TokenType::LeftBracket) {
+                let bracket = self.previous();
+                let index = self.expression()?;
+                self.consume(TokenType::RightBracket, "Expected ']' after index.")?;
+                expr = Expr::Index(Box::new(expr), bracket, Box::new(index));
+            } else if self.match_token(TokenType::QuestionDot) {
+                let name =
+                    self.consume(TokenType::Identifier, "Expected property name after '?.'.")?;
+                expr = Expr::OptionalGet(Box::new(expr), name);
             } else {
                 break;
             }
@@ -490,16 +864,107 @@ impl<'a> Parser<'a> {
             return Ok(Expr::Literal(Literal::Number(n)));
         }
 
+        if let Some(TokenType::Integer(n)) = match_literal_token!(self, TokenType::Integer) {
+            return Ok(Expr::Literal(Literal::Integer(n)));
+        }
+
+        if let Some(TokenType::BigInt(n)) = match_literal_token!(self, TokenType::BigInt) {
+            return Ok(Expr::Literal(Literal::BigInt(n)));
+        }
+
+        if let Some(TokenType::Decimal(n)) = match_literal_token!(self, TokenType::Decimal) {
+            return Ok(Expr::Literal(Literal::Decimal(n)));
+        }
+
         if let Some(TokenType::String(s)) = match_literal_token!(self, TokenType::String) {
             return Ok(Expr::Literal(Literal::String(s)));
         }
 
+        if let Some(TokenType::Interpolation(s)) =
+            match_literal_token!(self, TokenType::Interpolation)
+        {
+            let token = self.previous();
+            let mut parts = vec![Expr::Literal(Literal::String(s))];
+
+            loop {
+                parts.push(self.expression()?);
+
+                if let Some(TokenType::Interpolation(s)) =
+                    match_literal_token!(self, TokenType::Interpolation)
+                {
+                    parts.push(Expr::Literal(Literal::String(s)));
+                } else if let Some(TokenType::String(s)) =
+                    match_literal_token!(self, TokenType::String)
+                {
+                    parts.push(Expr::Literal(Literal::String(s)));
+                    break;
+                } else {
+                    return Err(
+                        self.error(self.peek(), "Expected '}' after interpolated expression.")
+                    );
+                }
+            }
+
+            return Ok(Expr::Interpolation(token, parts));
+        }
+
         if self.match_token(TokenType::LeftParen) {
+            let paren = self.previous();
             let expr = self.expression()?;
+
+            if self.match_token(TokenType::Comma) {
+                let mut elements = vec![expr];
+
+                while !self.check(TokenType::RightParen) && !self.is_at_end() {
+                    elements.push(self.expression()?);
+
+                    if !self.match_token(TokenType::Comma) {
+                        break;
+                    }
+                }
+
+                self.consume(TokenType::RightParen, "Expected ')' after tuple elements.")?;
+                return Ok(Expr::Tuple(paren, elements));
+            }
+
             self.consume(TokenType::RightParen, "Expected ')' after expression.")?;
             return Ok(Expr::Grouping(Box::new(expr)));
         }
 
+        if self.match_token(TokenType::LeftBracket) {
+            let bracket = self.previous();
+            let mut elements = Vec::new();
+
+            while !self.check(TokenType::RightBracket) && !self.is_at_end() {
+                elements.push(self.expression()?);
+
+                if !self.match_token(TokenType::Comma) {
+                    break;
+                }
+            }
+
+            self.consume(TokenType::RightBracket, "Expected ']' after list elements.")?;
+            return Ok(Expr::List(bracket, elements));
+        }
+
+        if self.match_token(TokenType::LeftBrace) {
+            let brace = self.previous();
+            let mut entries = Vec::new();
+
+            while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+                let key = self.expression()?;
+                self.consume(TokenType::Colon, "Expected ':' after map key.")?;
+                entries.push((key, self.expression()?));
+
+                if !self.match_token(TokenType::Comma) {
+                    break;
+                }
+            }
+
+            self.consume(TokenType::RightBrace, "Expected '}' after map entries.")?;
+            return Ok(Expr::Map(brace, entries));
+        }
+
         if self.match_token(TokenType::Self_) {
             return Ok(Expr::Self_(self.previous()));
         }
@@ -515,9 +980,168 @@ impl<'a> Parser<'a> {
             return Ok(Expr::Variable(self.previous()));
         }
 
+        if self.match_token(TokenType::Match) {
+            return self.match_expression();
+        }
+
         Err(self.error(self.peek(), "Expected expression."))
     }
 
+    fn match_expression(&mut self) -> ParseResult<Expr> {
+        let keyword = self.previous();
+        let subject = self.expression()?;
+
+        self.consume(TokenType::LeftBrace, "Expected '{' after match subject.")?;
+
+        let mut arms = Vec::new();
+
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let pattern = self.pattern()?;
+
+            let guard = if self.match_token(TokenType::If) {
+                Some(self.expression()?)
+            } else {
+                None
+            };
+
+            self.consume(TokenType::FatArrow, "Expected '=>' after match pattern.")?;
+
+            let body = if self.check(TokenType::LeftBrace) {
+                self.block_statement()?
+            } else {
+                Stmt::Expression(self.expression()?)
+            };
+
+            let is_block = matches!(body, Stmt::Block(_));
+
+            arms.push(MatchArm {
+                pattern,
+                guard,
+                body: Box::new(body),
+            });
+
+            if !self.match_token(TokenType::Comma) && !is_block {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expected '}' after match arms.")?;
+
+        Ok(Expr::Match(keyword, Box::new(subject), arms))
+    }
+
+    fn pattern(&mut self) -> ParseResult<Pattern> {
+        let first = self.pattern_alternative()?;
+
+        if !self.check(TokenType::Pipe) {
+            return Ok(first);
+        }
+
+        let mut alternatives = vec![first];
+
+        while self.match_token(TokenType::Pipe) {
+            alternatives.push(self.pattern_alternative()?);
+        }
+
+        Ok(Pattern::Alternatives(alternatives))
+    }
+
+    fn pattern_alternative(&mut self) -> ParseResult<Pattern> {
+        if self.match_token(TokenType::Identifier) {
+            let name = self.previous();
+
+            if name.lexeme == "_" {
+                return Ok(Pattern::Wildcard(name));
+            }
+
+            if self.match_token(TokenType::Dot) {
+                let variant = self.consume(TokenType::Identifier, "Expected variant name.")?;
+
+                let payload = if self.match_token(TokenType::LeftParen) {
+                    let mut patterns = Vec::new();
+
+                    if !self.check(TokenType::RightParen) {
+                        loop {
+                            patterns.push(self.pattern()?);
+
+                            if !self.match_token(TokenType::Comma) {
+                                break;
+                            }
+                        }
+                    }
+
+                    self.consume(
+                        TokenType::RightParen,
+                        "Expected ')' after variant patterns.",
+                    )?;
+
+                    Some(patterns)
+                } else {
+                    None
+                };
+
+                return Ok(Pattern::Variant(name, variant, payload));
+            }
+
+            if !self.match_token(TokenType::LeftBrace) {
+                return Ok(Pattern::Binding(name));
+            }
+
+            let mut fields = Vec::new();
+
+            if !self.check(TokenType::RightBrace) {
+                loop {
+                    let field = self.consume(TokenType::Identifier, "Expected field name.")?;
+
+                    let pattern = if self.match_token(TokenType::Colon) {
+                        Some(self.pattern()?)
+                    } else {
+                        None
+                    };
+
+                    fields.push((field, pattern));
+
+                    if !self.match_token(TokenType::Comma) || self.check(TokenType::RightBrace) {
+                        break;
+                    }
+                }
+            }
+
+            self.consume(TokenType::RightBrace, "Expected '}' after field patterns.")?;
+
+            return Ok(Pattern::Instance(name, fields));
+        }
+
+        let token = self.peek();
+        let start = self.pattern_literal()?;
+
+        if self.match_token(TokenType::DotDot) || self.match_token(TokenType::DotDotEqual) {
+            let inclusive = self.previous().ty == TokenType::DotDotEqual;
+            let end = self.pattern_literal()?;
+
+            return Ok(Pattern::Range(token, start, end, inclusive));
+        }
+
+        Ok(Pattern::Literal(token, start))
+    }
+
+    fn pattern_literal(&mut self) -> ParseResult<Literal> {
+        if self.match_token(TokenType::Minus) {
+            return match self.pattern_literal()? {
+                Literal::Number(n) => Ok(Literal::Number(-n)),
+                Literal::Integer(n) => Ok(Literal::Integer(-n)),
+                Literal::BigInt(n) => Ok(Literal::BigInt(-n)),
+                Literal::Decimal(n) => Ok(Literal::Decimal(-n)),
+                _ => Err(self.error(self.previous(), "Expected number after '-' in pattern.")),
+            };
+        }
+
+        match self.primary()? {
+            Expr::Literal(literal) => Ok(literal),
+            _ => Err(self.error(self.previous(), "Expected pattern.")),
+        }
+    }
+
     fn match_token(&mut self, token_type: TokenType) -> bool {
         if self.check(token_type) {
             self.advance();
@@ -561,11 +1185,13 @@ impl<'a> Parser<'a> {
 
             match self.peek().ty {
                 TokenType::Class
+                | TokenType::Enum
                 | TokenType::Fn
                 | TokenType::Var
                 | TokenType::For
                 | TokenType::If
                 | TokenType::While
+                | TokenType::Try
                 | TokenType::Return => return,
                 _ => {}
             }
@@ -589,12 +1215,8 @@ impl<'a> Parser<'a> {
     fn error(&mut self, token: Token, message: &str) -> ParseError {
         let error = ParseError::new(token.clone(), message.into());
 
-        self.errors.add(HezenError::syntax_error(
-            token.position.file,
-            token.position.line,
-            token.position.column,
-            message.into(),
-        ));
+        self.errors
+            .add(HezenError::Syntax(token.position, message.into()));
 
         error
     }
@@ -602,13 +1224,17 @@ impl<'a> Parser<'a> {
 
 #[derive(Debug)]
 pub struct ParseError {
-    token: Token,
+    /// Boxed to keep the `Err` of every parse result small.
+    token: Box<Token>,
     message: String,
 }
 
 impl ParseError {
     pub fn new(token: Token, message: String) -> Self {
-        Self { token, message }
+        Self {
+            token: Box::new(token),
+            message,
+        }
     }
 }
 
@@ -627,3 +1253,9 @@ impl Display for ParseError {
 }
 
 impl Error for ParseError {}
+
+/// Whether `second` starts right where `first` ends, with nothing between.
+fn adjacent(first: &Token, second: &Token) -> bool {
+    second.position.start_line == first.position.line
+        && second.position.start_column == first.position.column + 1
+}
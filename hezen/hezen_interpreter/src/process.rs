@@ -0,0 +1,52 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{environment::HezenValue, function::HezenNativeFunction, module::native_error};
+
+/// Natives that read the script's arguments and environment variables.
+pub fn environment_functions() -> Vec<HezenNativeFunction> {
+    vec![
+        HezenNativeFunction::with_interpreter("args", 0, |interpreter, _, _| {
+            Ok(HezenValue::List(Rc::new(RefCell::new(
+                interpreter
+                    .args()
+                    .iter()
+                    .cloned()
+                    .map(HezenValue::String)
+                    .collect(),
+            ))))
+        }),
+        HezenNativeFunction::builtin("env", 1, |paren, args| match &args[0] {
+            HezenValue::String(name) => Ok(match std::env::var(name) {
+                Ok(value) => HezenValue::String(value),
+                Err(_) => HezenValue::Nil,
+            }),
+            other => Err(native_error(
+                paren,
+                format!(
+                    "Expected argument 1 of 'env' to be a string, not '{}'",
+                    other.type_name()
+                ),
+            )),
+        }),
+    ]
+}
+
+/// The `exit` native, which ends the run with an exit code.
+pub fn exit_functions() -> Vec<HezenNativeFunction> {
+    vec![HezenNativeFunction::with_interpreter(
+        "exit",
+        1,
+        |interpreter, paren, args| match &args[0] {
+            HezenValue::Integer(code) if i32::try_from(*code).is_ok() => {
+                Err(interpreter.exit(paren, *code as i32))
+            }
+            other => Err(native_error(
+                paren,
+                format!(
+                    "Expected argument 1 of 'exit' to be an integer that fits in an i32, not '{}'",
+                    other.type_name()
+                ),
+            )),
+        },
+    )]
+}
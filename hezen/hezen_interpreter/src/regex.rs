@@ -0,0 +1,217 @@
+use std::{cell::RefCell, mem::size_of, rc::Rc};
+
+use hezen_core::error::HezenError;
+use indexmap::IndexMap;
+use regex::Regex;
+
+use crate::{
+    class::HezenClass,
+    environment::HezenValue,
+    instance::HezenInstanceHandle,
+    interpreter::Interpreter,
+    limits::{entry_size, Allowance},
+    module::{native_error, native_error_with_kind},
+    token::Token,
+};
+
+type RegexFunction = fn(&Token, Allowance, &Regex, &[HezenValue]) -> Result<HezenValue, HezenError>;
+
+/// The `Regex` class. `Regex(pattern)` compiles the pattern, raising a
+/// `RegexError` when it is invalid, and keeps it in the `pattern` field.
+pub fn class() -> HezenClass {
+    HezenClass::native("Regex")
+        .method("init", 1, init)
+        .method("is_match", 1, compiled(is_match))
+        .method("find", 1, compiled(find))
+        .method("find_all", 1, compiled(find_all))
+        .method("captures", 1, compiled(captures))
+        .method("replace", 2, compiled(replace))
+}
+
+fn init(
+    _: &mut Interpreter,
+    paren: &Token,
+    instance: &HezenInstanceHandle,
+    arguments: &[HezenValue],
+) -> Result<HezenValue, HezenError> {
+    let pattern = string_argument(paren, "init", arguments, 0)?;
+    instance.set_data(compile(paren, pattern)?);
+    instance.set(
+        "pattern".to_string(),
+        HezenValue::String(pattern.to_string()),
+    );
+
+    Ok(HezenValue::Nil)
+}
+
+fn compile(paren: &Token, pattern: &str) -> Result<Regex, HezenError> {
+    Regex::new(pattern).map_err(|why| {
+        // The syntax errors repeat the pattern over several lines; keep only
+        // the description at the end.
+        let why = why.to_string();
+        let why = why.lines().last().unwrap_or_default();
+        native_error_with_kind(
+            paren,
+            "RegexError",
+            format!(
+                "Invalid pattern '{pattern}': {}",
+                why.trim_start_matches("error: ")
+            ),
+        )
+    })
+}
+
+/// Makes `function` a method that runs on the regex compiled by `init`,
+/// which subclasses must call through `super.init(pattern)`.
+fn compiled(
+    function: RegexFunction,
+) -> impl Fn(
+    &mut Interpreter,
+    &Token,
+    &HezenInstanceHandle,
+    &[HezenValue],
+) -> Result<HezenValue, HezenError> {
+    move |interpreter, paren, instance, arguments| {
+        let Some(regex) = instance.data::<Regex>() else {
+            return Err(native_error(
+                paren,
+                format!(
+                    "'{}' has no pattern; its 'init' must call 'super.init(pattern)'",
+                    instance.type_name()
+                ),
+            ));
+        };
+
+        function(paren, interpreter.allowance(), &regex, arguments)
+    }
+}
+
+fn string_argument<'a>(
+    paren: &Token,
+    method: &str,
+    arguments: &'a [HezenValue],
+    index: usize,
+) -> Result<&'a str, HezenError> {
+    match &arguments[index] {
+        HezenValue::String(s) => Ok(s),
+        other => Err(native_error(
+            paren,
+            format!(
+                "Expected argument {} of 'Regex.{}' to be a string, not '{}'",
+                index + 1,
+                method,
+                other.type_name()
+            ),
+        )),
+    }
+}
+
+fn is_match(
+    paren: &Token,
+    _: Allowance,
+    regex: &Regex,
+    arguments: &[HezenValue],
+) -> Result<HezenValue, HezenError> {
+    let text = string_argument(paren, "is_match", arguments, 0)?;
+
+    Ok(HezenValue::Bool(regex.is_match(text)))
+}
+
+/// The first match, or `nil`.
+fn find(
+    paren: &Token,
+    _: Allowance,
+    regex: &Regex,
+    arguments: &[HezenValue],
+) -> Result<HezenValue, HezenError> {
+    let text = string_argument(paren, "find", arguments, 0)?;
+
+    Ok(match regex.find(text) {
+        Some(found) => HezenValue::String(found.as_str().to_string()),
+        None => HezenValue::Nil,
+    })
+}
+
+/// A list of every non-overlapping match.
+fn find_all(
+    paren: &Token,
+    allowance: Allowance,
+    regex: &Regex,
+    arguments: &[HezenValue],
+) -> Result<HezenValue, HezenError> {
+    let text = string_argument(paren, "find_all", arguments, 0)?;
+
+    let mut size = size_of::<HezenValue>();
+    let mut matches = Vec::new();
+    for found in regex.find_iter(text) {
+        size += size_of::<HezenValue>() + found.as_str().len();
+        allowance.check(size, paren)?;
+
+        matches.push(HezenValue::String(found.as_str().to_string()));
+    }
+
+    Ok(HezenValue::List(Rc::new(RefCell::new(matches))))
+}
+
+/// The groups of the first match as a map, or `nil` when nothing matches.
+/// Every group is keyed by its number and named groups also by their name;
+/// groups that did not take part in the match are `nil`.
+fn captures(
+    paren: &Token,
+    allowance: Allowance,
+    regex: &Regex,
+    arguments: &[HezenValue],
+) -> Result<HezenValue, HezenError> {
+    let text = string_argument(paren, "captures", arguments, 0)?;
+
+    let Some(captures) = regex.captures(text) else {
+        return Ok(HezenValue::Nil);
+    };
+
+    let mut size = size_of::<HezenValue>();
+    let mut groups = IndexMap::new();
+    for (i, name) in regex.capture_names().enumerate() {
+        let group = captures.get(i).map(|group| group.as_str());
+        size += entry_size(&i.to_string()) + group.map_or(0, str::len);
+        if let Some(name) = name {
+            size += entry_size(name) + group.map_or(0, str::len);
+        }
+        allowance.check(size, paren)?;
+
+        let value = group.map_or(HezenValue::Nil, |group| {
+            HezenValue::String(group.to_string())
+        });
+
+        if let Some(name) = name {
+            groups.insert(name.to_string(), value.clone());
+        }
+        groups.insert(i.to_string(), value);
+    }
+
+    Ok(HezenValue::Map(Rc::new(RefCell::new(groups))))
+}
+
+/// Replaces every match. `$1` and `$name` in the replacement refer to groups.
+fn replace(
+    paren: &Token,
+    allowance: Allowance,
+    regex: &Regex,
+    arguments: &[HezenValue],
+) -> Result<HezenValue, HezenError> {
+    let text = string_argument(paren, "replace", arguments, 0)?;
+    let replacement = string_argument(paren, "replace", arguments, 1)?;
+
+    let mut replaced = String::new();
+    let mut last = 0;
+    for captures in regex.captures_iter(text) {
+        let found = captures.get(0).unwrap();
+        replaced.push_str(&text[last..found.start()]);
+        captures.expand(replacement, &mut replaced);
+        last = found.end();
+
+        allowance.check(replaced.len(), paren)?;
+    }
+    replaced.push_str(&text[last..]);
+
+    Ok(HezenValue::String(replaced))
+}
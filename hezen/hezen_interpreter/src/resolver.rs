@@ -1,9 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use hezen_core::error::{HezenError, HezenErrorList};
 
 use crate::{
-    ast::{Expr, Stmt},
+    ast::{Destructure, Expr, Literal, MatchArm, Pattern, Stmt},
     class::ClassType,
     function::FunctionType,
     interpreter::Interpreter,
@@ -69,6 +69,25 @@ impl<'a> Resolver<'a> {
         scope.insert(name.lexeme.clone(), true);
     }
 
+    fn declare_target(&mut self, target: &Destructure) {
+        match target {
+            Destructure::Name(name) => {
+                self.declare(name);
+                self.define(name);
+            }
+            Destructure::Sequence(_, targets, rest) => {
+                for target in targets {
+                    self.declare_target(target);
+                }
+
+                if let Some(rest) = rest {
+                    self.declare(rest);
+                    self.define(rest);
+                }
+            }
+        }
+    }
+
     fn resolve_local(&mut self, name: &Token, _expr: &Expr) {
         for (i, scope) in self.scopes.iter().rev().enumerate() {
             if scope.contains_key(&name.lexeme) {
@@ -84,7 +103,7 @@ impl<'a> Resolver<'a> {
 
         self.begin_scope();
 
-        if let Stmt::Function(name, params, body) = function {
+        if let Stmt::Function(name, params, body, _) = function {
             for param in params {
                 self.declare(param);
                 self.define(param);
@@ -93,10 +112,8 @@ impl<'a> Resolver<'a> {
             if let Stmt::Block(statements) = &**body {
                 self.internal_resolve(statements);
             } else {
-                self.errors.add(HezenError::runtime(
-                    name.position.file.clone(),
-                    name.position.line,
-                    name.position.column,
+                self.errors.add(HezenError::Runtime(
+                    name.position.clone(),
                     "Expected block statement in function body".into(),
                 ));
             }
@@ -116,7 +133,7 @@ impl<'a> Resolver<'a> {
                 self.internal_resolve(statements);
                 self.end_scope();
             }
-            Stmt::Class(name, superclass, methods) => {
+            Stmt::Class(name, superclass, methods, _) => {
                 let enclosing_class = self.current_class;
                 self.current_class = ClassType::Class;
 
@@ -148,7 +165,7 @@ impl<'a> Resolver<'a> {
                     .insert("self".to_string(), true);
 
                 for method in methods {
-                    if let Stmt::Function(name, _, _) = method {
+                    if let Stmt::Function(name, _, _, _) = method {
                         let declaration = if name.lexeme == "init" {
                             FunctionType::Initializer
                         } else {
@@ -169,8 +186,28 @@ impl<'a> Resolver<'a> {
 
                 self.current_class = enclosing_class;
             }
+            Stmt::Destructure(target, value, _) => {
+                self.resolve_expr(value);
+                self.declare_target(target);
+            }
+            Stmt::Enum(name, variants) => {
+                self.declare(name);
+                self.define(name);
+
+                let mut seen = HashSet::new();
+
+                for (variant, _) in variants {
+                    if !seen.insert(&variant.lexeme) {
+                        self.error(
+                            variant.clone(),
+                            &format!("Duplicate variant '{}' in enum.", variant.lexeme),
+                        );
+                    }
+                }
+            }
+            Stmt::Expression(Expr::Match(_, subject, arms)) => self.resolve_match(subject, arms),
             Stmt::Expression(expr) => self.resolve_expr(expr),
-            Stmt::Function(name, _, _) => {
+            Stmt::Function(name, _, _, _) => {
                 self.declare(name);
                 self.define(name);
 
@@ -207,10 +244,26 @@ impl<'a> Resolver<'a> {
                 }
                 self.define(name);
             }
-            Stmt::While(condition, body) => {
+            Stmt::While(_, condition, body) => {
                 self.resolve_expr(condition);
                 self.resolve_stmt(body);
             }
+            Stmt::Try(body, name, handler) => {
+                self.resolve_stmt(body);
+
+                self.begin_scope();
+
+                if let Some(name) = name {
+                    self.declare(name);
+                    self.define(name);
+                }
+
+                if let Stmt::Block(statements) = &**handler {
+                    self.internal_resolve(statements);
+                }
+
+                self.end_scope();
+            }
             Stmt::Break => {}
             Stmt::Continue => {}
         }
@@ -233,17 +286,60 @@ impl<'a> Resolver<'a> {
                     self.resolve_expr(argument);
                 }
             }
+            Expr::CompoundAssign(name, _, value) => {
+                self.resolve_expr(value);
+                self.resolve_local(name, expr);
+            }
+            Expr::CompoundSet(object, _, _, value) => {
+                self.resolve_expr(object);
+                self.resolve_expr(value);
+            }
+            Expr::CompoundSetIndex(object, _, index, _, value) => {
+                self.resolve_expr(object);
+                self.resolve_expr(index);
+                self.resolve_expr(value);
+            }
+            Expr::Conditional(condition, then_expr, else_expr) => {
+                self.resolve_expr(condition);
+                self.resolve_expr(then_expr);
+                self.resolve_expr(else_expr);
+            }
             Expr::Get(object, _) => self.resolve_expr(object),
             Expr::Grouping(expr) => self.resolve_expr(expr),
+            Expr::Index(object, _, index) => {
+                self.resolve_expr(object);
+                self.resolve_expr(index);
+            }
+            Expr::Interpolation(_, elements)
+            | Expr::List(_, elements)
+            | Expr::Tuple(_, elements) => {
+                for element in elements {
+                    self.resolve_expr(element);
+                }
+            }
             Expr::Literal(_) => {}
             Expr::Logical(left, _, right) => {
                 self.resolve_expr(left);
                 self.resolve_expr(right);
             }
+            Expr::Map(_, entries) => {
+                for (key, value) in entries {
+                    self.resolve_expr(key);
+                    self.resolve_expr(value);
+                }
+            }
+            Expr::Match(_, subject, arms) => self.resolve_match(subject, arms),
+            Expr::OptionalGet(object, _) => self.resolve_expr(object),
+            Expr::Postfix(assignment) => self.resolve_expr(assignment),
             Expr::Set(object, _, value) => {
                 self.resolve_expr(object);
                 self.resolve_expr(value);
             }
+            Expr::SetIndex(object, _, index, value) => {
+                self.resolve_expr(object);
+                self.resolve_expr(index);
+                self.resolve_expr(value);
+            }
             Expr::Super(keyword, _) => {
                 if self.current_class == ClassType::None {
                     self.error(keyword.clone(), "Cannot use 'super' outside of a class.");
@@ -280,12 +376,161 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    fn resolve_match(&mut self, subject: &Expr, arms: &[MatchArm]) {
+        self.resolve_expr(subject);
+
+        let mut exhausted = false;
+        let mut seen_literals = HashSet::new();
+
+        for arm in arms {
+            let literals = pattern_literals(&arm.pattern);
+
+            let covered = !literals.is_empty()
+                && literals
+                    .iter()
+                    .all(|literal| seen_literals.contains(*literal));
+
+            if exhausted || covered {
+                self.warning(
+                    pattern_token(&arm.pattern).clone(),
+                    "Unreachable match arm.",
+                );
+            }
+
+            self.begin_scope();
+
+            self.resolve_pattern(&arm.pattern);
+
+            if let Some(guard) = &arm.guard {
+                self.resolve_expr(guard);
+            }
+
+            self.resolve_stmt(&arm.body);
+
+            self.end_scope();
+
+            if arm.guard.is_none() {
+                exhausted |= is_irrefutable(&arm.pattern);
+                seen_literals.extend(literals);
+            }
+        }
+    }
+
+    fn resolve_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Wildcard(_) | Pattern::Literal(..) | Pattern::Range(..) => {}
+            Pattern::Binding(name) => {
+                self.declare(name);
+                self.define(name);
+            }
+            Pattern::Instance(class, fields) => {
+                self.resolve_local(class, &Expr::Variable(class.clone()));
+
+                for (field, pattern) in fields {
+                    match pattern {
+                        Some(pattern) => self.resolve_pattern(pattern),
+                        None => {
+                            self.declare(field);
+                            self.define(field);
+                        }
+                    }
+                }
+            }
+            Pattern::Variant(enumeration, _, payload) => {
+                self.resolve_local(enumeration, &Expr::Variable(enumeration.clone()));
+
+                for pattern in payload.iter().flatten() {
+                    self.resolve_pattern(pattern);
+                }
+            }
+            Pattern::Alternatives(patterns) => {
+                // Whichever alternative matches, the arm sees the same names.
+                let expected = pattern_bindings(&patterns[0]);
+
+                for pattern in &patterns[1..] {
+                    let bindings = pattern_bindings(pattern);
+
+                    if let Some(name) = expected.symmetric_difference(&bindings).next() {
+                        self.error(
+                            pattern_token(pattern).clone(),
+                            &format!("Variable '{name}' is not bound in all alternatives."),
+                        );
+                    }
+                }
+
+                for pattern in patterns {
+                    self.resolve_pattern(pattern);
+                }
+            }
+        }
+    }
+
+    fn warning(&mut self, token: Token, message: &str) {
+        self.errors
+            .add(HezenError::Warning(token.position, message.into()));
+    }
+
     fn error(&mut self, token: Token, message: &str) {
-        self.errors.add(HezenError::validation(
-            token.position.file,
-            token.position.line,
-            token.position.column,
-            message.into(),
-        ));
+        self.errors
+            .add(HezenError::Validation(token.position, message.into()));
+    }
+}
+
+fn pattern_token(pattern: &Pattern) -> &Token {
+    match pattern {
+        Pattern::Wildcard(token)
+        | Pattern::Literal(token, _)
+        | Pattern::Range(token, ..)
+        | Pattern::Binding(token)
+        | Pattern::Instance(token, _)
+        | Pattern::Variant(token, ..) => token,
+        Pattern::Alternatives(patterns) => pattern_token(&patterns[0]),
+    }
+}
+
+/// The names a pattern binds.
+fn pattern_bindings(pattern: &Pattern) -> BTreeSet<String> {
+    match pattern {
+        Pattern::Wildcard(_) | Pattern::Literal(..) | Pattern::Range(..) => BTreeSet::new(),
+        Pattern::Binding(name) => BTreeSet::from([name.lexeme.clone()]),
+        Pattern::Instance(_, fields) => fields
+            .iter()
+            .flat_map(|(field, pattern)| match pattern {
+                Some(pattern) => pattern_bindings(pattern),
+                None => BTreeSet::from([field.lexeme.clone()]),
+            })
+            .collect(),
+        Pattern::Variant(_, _, payload) => payload
+            .iter()
+            .flatten()
+            .flat_map(pattern_bindings)
+            .collect(),
+        Pattern::Alternatives(patterns) => pattern_bindings(&patterns[0]),
+    }
+}
+
+fn is_irrefutable(pattern: &Pattern) -> bool {
+    match pattern {
+        Pattern::Wildcard(_) | Pattern::Binding(_) => true,
+        Pattern::Alternatives(patterns) => patterns.iter().any(is_irrefutable),
+        _ => false,
+    }
+}
+
+/// The literals a pattern consists of, or nothing if any part of it is not a
+/// plain literal.
+fn pattern_literals(pattern: &Pattern) -> Vec<&Literal> {
+    match pattern {
+        Pattern::Literal(_, literal) => vec![literal],
+        Pattern::Alternatives(patterns) => {
+            let literals = patterns.iter().map(pattern_literals).collect::<Vec<_>>();
+
+            if literals.iter().any(|l| l.is_empty()) {
+                Vec::new()
+            } else {
+                literals.into_iter().flatten().collect()
+            }
+        }
+        _ => Vec::new(),
     }
 }
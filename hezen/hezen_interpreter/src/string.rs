@@ -0,0 +1,230 @@
+use std::{cell::RefCell, mem::size_of, rc::Rc};
+
+use crate::{environment::HezenValue, function::HezenNativeFunction, module::native_error};
+
+type StringFunction = fn(&str, &[HezenValue]) -> Result<HezenValue, String>;
+
+/// Roughly the most a method allocates for its receiver and arguments,
+/// checked against the memory limit before the method runs.
+type StringSize = fn(&str, &[HezenValue]) -> usize;
+
+/// The built-in method `name` of a string, bound to that string, or `None`
+/// when strings have no such method. All positions and lengths count
+/// characters, not bytes.
+pub fn method(receiver: &str, name: &str) -> Option<HezenNativeFunction> {
+    let (arity, function, size): (usize, StringFunction, StringSize) = match name {
+        "len" => (0, len, nothing),
+        "upper" => (0, upper, copy),
+        "lower" => (0, lower, copy),
+        "trim" => (0, trim, copy),
+        "split" => (1, split, split_size),
+        "replace" => (2, replace, replace_size),
+        "find" => (1, find, nothing),
+        "starts_with" => (1, starts_with, nothing),
+        "ends_with" => (1, ends_with, nothing),
+        "chars" => (0, chars, chars_size),
+        "substring" => (2, substring, copy),
+        "repeat" => (1, repeat, repeat_size),
+        _ => return None,
+    };
+
+    let receiver = receiver.to_string();
+
+    Some(HezenNativeFunction::with_interpreter(
+        &format!("string.{name}"),
+        arity,
+        move |interpreter, paren, arguments| {
+            interpreter
+                .allowance()
+                .check(size(&receiver, arguments), paren)?;
+
+            function(&receiver, arguments).map_err(|message| native_error(paren, message))
+        },
+    ))
+}
+
+fn nothing(_: &str, _: &[HezenValue]) -> usize {
+    0
+}
+
+fn copy(receiver: &str, _: &[HezenValue]) -> usize {
+    size_of::<HezenValue>() + receiver.len()
+}
+
+/// The size of a list of `count` strings holding `bytes` between them.
+fn list_size(count: usize, bytes: usize) -> usize {
+    size_of::<HezenValue>()
+        .saturating_mul(count.saturating_add(1))
+        .saturating_add(bytes)
+}
+
+fn split_size(receiver: &str, arguments: &[HezenValue]) -> usize {
+    match &arguments[0] {
+        HezenValue::String(separator) if !separator.is_empty() => list_size(
+            receiver.matches(separator.as_str()).count() + 1,
+            receiver.len(),
+        ),
+        _ => chars_size(receiver, arguments),
+    }
+}
+
+fn chars_size(receiver: &str, _: &[HezenValue]) -> usize {
+    list_size(receiver.chars().count(), receiver.len())
+}
+
+fn replace_size(receiver: &str, arguments: &[HezenValue]) -> usize {
+    match (&arguments[0], &arguments[1]) {
+        (HezenValue::String(from), HezenValue::String(to)) => {
+            let matches = if from.is_empty() {
+                receiver.chars().count() + 1
+            } else {
+                receiver.matches(from.as_str()).count()
+            };
+
+            matches
+                .saturating_mul(to.len())
+                .saturating_add(copy(receiver, arguments))
+        }
+        _ => 0,
+    }
+}
+
+fn repeat_size(receiver: &str, arguments: &[HezenValue]) -> usize {
+    match &arguments[0] {
+        HezenValue::Integer(count) if *count >= 0 => receiver
+            .len()
+            .saturating_mul(*count as usize)
+            .saturating_add(size_of::<HezenValue>()),
+        _ => 0,
+    }
+}
+
+fn string_argument<'a>(
+    method: &str,
+    arguments: &'a [HezenValue],
+    index: usize,
+) -> Result<&'a str, String> {
+    match &arguments[index] {
+        HezenValue::String(s) => Ok(s),
+        other => Err(format!(
+            "Expected argument {} of '{}' to be a string, not '{}'",
+            index + 1,
+            method,
+            other.type_name()
+        )),
+    }
+}
+
+fn count_argument(method: &str, arguments: &[HezenValue], index: usize) -> Result<usize, String> {
+    match &arguments[index] {
+        HezenValue::Integer(i) if *i >= 0 => Ok(*i as usize),
+        HezenValue::Integer(i) => Err(format!(
+            "Expected argument {} of '{}' to be a non-negative integer, not {}",
+            index + 1,
+            method,
+            i
+        )),
+        other => Err(format!(
+            "Expected argument {} of '{}' to be a non-negative integer, not '{}'",
+            index + 1,
+            method,
+            other.type_name()
+        )),
+    }
+}
+
+fn string_list(strings: impl Iterator<Item = String>) -> HezenValue {
+    HezenValue::List(Rc::new(RefCell::new(
+        strings.map(HezenValue::String).collect(),
+    )))
+}
+
+fn len(receiver: &str, _: &[HezenValue]) -> Result<HezenValue, String> {
+    Ok(HezenValue::Integer(receiver.chars().count() as i64))
+}
+
+fn upper(receiver: &str, _: &[HezenValue]) -> Result<HezenValue, String> {
+    Ok(HezenValue::String(receiver.to_uppercase()))
+}
+
+fn lower(receiver: &str, _: &[HezenValue]) -> Result<HezenValue, String> {
+    Ok(HezenValue::String(receiver.to_lowercase()))
+}
+
+fn trim(receiver: &str, _: &[HezenValue]) -> Result<HezenValue, String> {
+    Ok(HezenValue::String(receiver.trim().to_string()))
+}
+
+fn split(receiver: &str, arguments: &[HezenValue]) -> Result<HezenValue, String> {
+    let separator = string_argument("split", arguments, 0)?;
+
+    if separator.is_empty() {
+        return chars(receiver, arguments);
+    }
+
+    Ok(string_list(receiver.split(separator).map(String::from)))
+}
+
+fn replace(receiver: &str, arguments: &[HezenValue]) -> Result<HezenValue, String> {
+    let from = string_argument("replace", arguments, 0)?;
+    let to = string_argument("replace", arguments, 1)?;
+
+    Ok(HezenValue::String(receiver.replace(from, to)))
+}
+
+fn find(receiver: &str, arguments: &[HezenValue]) -> Result<HezenValue, String> {
+    let needle = string_argument("find", arguments, 0)?;
+
+    Ok(match receiver.find(needle) {
+        Some(byte) => HezenValue::Integer(receiver[..byte].chars().count() as i64),
+        None => HezenValue::Nil,
+    })
+}
+
+fn starts_with(receiver: &str, arguments: &[HezenValue]) -> Result<HezenValue, String> {
+    let prefix = string_argument("starts_with", arguments, 0)?;
+
+    Ok(HezenValue::Bool(receiver.starts_with(prefix)))
+}
+
+fn ends_with(receiver: &str, arguments: &[HezenValue]) -> Result<HezenValue, String> {
+    let suffix = string_argument("ends_with", arguments, 0)?;
+
+    Ok(HezenValue::Bool(receiver.ends_with(suffix)))
+}
+
+fn chars(receiver: &str, _: &[HezenValue]) -> Result<HezenValue, String> {
+    Ok(string_list(receiver.chars().map(String::from)))
+}
+
+fn substring(receiver: &str, arguments: &[HezenValue]) -> Result<HezenValue, String> {
+    let start = count_argument("substring", arguments, 0)?;
+    let end = count_argument("substring", arguments, 1)?;
+    let len = receiver.chars().count();
+
+    if start > end || end > len {
+        return Err(format!(
+            "Substring range {start}..{end} out of range for length {len}"
+        ));
+    }
+
+    Ok(HezenValue::String(
+        receiver.chars().skip(start).take(end - start).collect(),
+    ))
+}
+
+/// The longest string, in bytes, that `repeat` builds.
+const MAX_REPEAT_LENGTH: usize = 1 << 30;
+
+fn repeat(receiver: &str, arguments: &[HezenValue]) -> Result<HezenValue, String> {
+    let count = count_argument("repeat", arguments, 0)?;
+
+    match receiver.len().checked_mul(count) {
+        Some(length) if length <= MAX_REPEAT_LENGTH => {
+            Ok(HezenValue::String(receiver.repeat(count)))
+        }
+        _ => Err(format!(
+            "Cannot repeat a string {count} times, the result would be too long"
+        )),
+    }
+}
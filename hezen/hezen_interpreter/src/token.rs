@@ -1,6 +1,8 @@
 use std::fmt::Display;
 
 use hezen_core::error::HezenLineInfo;
+use num_bigint::BigInt;
+use rust_decimal::Decimal;
 
 #[derive(Debug, Clone)]
 pub enum TokenType {
@@ -8,6 +10,8 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
     Dot,
     Minus,
@@ -15,6 +19,9 @@ pub enum TokenType {
     Semicolon,
     Slash,
     Star,
+    Percent,
+    Colon,
+    Pipe,
 
     // one or two character tokens.
     Bang,
@@ -25,11 +32,31 @@ pub enum TokenType {
     GreaterEqual,
     Less,
     LessEqual,
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    PercentEqual,
+    PlusPlus,
+    MinusMinus,
+    StarStar,
+    Question,
+    QuestionQuestion,
+    QuestionDot,
+    FatArrow,
+    DotDot,
+    DotDotEqual,
+    DotDotDot,
 
     // literals.
     Identifier,
     String(String),
+    Interpolation(String),
+    DocComment(String),
     Number(f64),
+    Integer(i64),
+    BigInt(BigInt),
+    Decimal(Decimal),
 
     // keywords.
     And,
@@ -50,6 +77,10 @@ pub enum TokenType {
     Mut,
     Break,
     Continue,
+    Match,
+    Enum,
+    Try,
+    Catch,
 
     Eof,
     Builtin,
@@ -59,7 +90,12 @@ impl PartialEq for TokenType {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::String(l0), Self::String(r0)) => l0 == r0,
+            (Self::Interpolation(l0), Self::Interpolation(r0)) => l0 == r0,
+            (Self::DocComment(l0), Self::DocComment(r0)) => l0 == r0,
             (Self::Number(l0), Self::Number(r0)) => l0 == r0,
+            (Self::Integer(l0), Self::Integer(r0)) => l0 == r0,
+            (Self::BigInt(l0), Self::BigInt(r0)) => l0 == r0,
+            (Self::Decimal(l0), Self::Decimal(r0)) => l0 == r0,
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
         }
     }
@@ -69,16 +105,17 @@ impl std::hash::Hash for TokenType {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         core::mem::discriminant(self).hash(state);
         match self {
-            Self::String(v) => v.hash(state),
+            Self::String(v) | Self::Interpolation(v) | Self::DocComment(v) => v.hash(state),
             Self::Number(v) => v.to_bits().hash(state),
+            Self::Integer(v) => v.hash(state),
+            Self::BigInt(v) => v.hash(state),
+            Self::Decimal(v) => v.hash(state),
             _ => {}
         }
     }
 }
 
-impl Eq for TokenType {
-    
-}
+impl Eq for TokenType {}
 
 impl Display for TokenType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -87,6 +124,8 @@ impl Display for TokenType {
             TokenType::RightParen => write!(f, ")"),
             TokenType::LeftBrace => write!(f, "{{"),
             TokenType::RightBrace => write!(f, "}}"),
+            TokenType::LeftBracket => write!(f, "["),
+            TokenType::RightBracket => write!(f, "]"),
             TokenType::Comma => write!(f, ","),
             TokenType::Dot => write!(f, "."),
             TokenType::Minus => write!(f, "-"),
@@ -94,6 +133,9 @@ impl Display for TokenType {
             TokenType::Semicolon => write!(f, ";"),
             TokenType::Slash => write!(f, "/"),
             TokenType::Star => write!(f, "*"),
+            TokenType::Percent => write!(f, "%"),
+            TokenType::Colon => write!(f, ":"),
+            TokenType::Pipe => write!(f, "|"),
             TokenType::Bang => write!(f, "!"),
             TokenType::BangEqual => write!(f, "!="),
             TokenType::Equal => write!(f, "="),
@@ -102,9 +144,29 @@ impl Display for TokenType {
             TokenType::GreaterEqual => write!(f, ">="),
             TokenType::Less => write!(f, "<"),
             TokenType::LessEqual => write!(f, "<="),
+            TokenType::PlusEqual => write!(f, "+="),
+            TokenType::MinusEqual => write!(f, "-="),
+            TokenType::StarEqual => write!(f, "*="),
+            TokenType::SlashEqual => write!(f, "/="),
+            TokenType::PercentEqual => write!(f, "%="),
+            TokenType::PlusPlus => write!(f, "++"),
+            TokenType::MinusMinus => write!(f, "--"),
+            TokenType::StarStar => write!(f, "**"),
+            TokenType::Question => write!(f, "?"),
+            TokenType::QuestionQuestion => write!(f, "??"),
+            TokenType::QuestionDot => write!(f, "?."),
+            TokenType::FatArrow => write!(f, "=>"),
+            TokenType::DotDot => write!(f, ".."),
+            TokenType::DotDotEqual => write!(f, "..="),
+            TokenType::DotDotDot => write!(f, "..."),
             TokenType::Identifier => write!(f, "identifier"),
             TokenType::String(s) => write!(f, "\"{s}\""),
+            TokenType::Interpolation(s) => write!(f, "\"{s}${{"),
+            TokenType::DocComment(s) => write!(f, "///{s}"),
             TokenType::Number(n) => write!(f, "{n}"),
+            TokenType::Integer(n) => write!(f, "{n}"),
+            TokenType::BigInt(n) => write!(f, "{n}"),
+            TokenType::Decimal(n) => write!(f, "{n}d"),
             TokenType::And => write!(f, "and"),
             TokenType::Class => write!(f, "class"),
             TokenType::Else => write!(f, "else"),
@@ -123,6 +185,10 @@ impl Display for TokenType {
             TokenType::Mut => write!(f, "mut"),
             TokenType::Break => write!(f, "break"),
             TokenType::Continue => write!(f, "continue"),
+            TokenType::Match => write!(f, "match"),
+            TokenType::Enum => write!(f, "enum"),
+            TokenType::Try => write!(f, "try"),
+            TokenType::Catch => write!(f, "catch"),
             TokenType::Eof => write!(f, "eof"),
             TokenType::Builtin => write!(f, "builtin"),
         }
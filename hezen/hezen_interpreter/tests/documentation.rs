@@ -0,0 +1,85 @@
+use hezen_interpreter::{document, Engine};
+
+const SOURCE: &str = "
+/// Adds two numbers.
+/// Returns their sum.
+fn add(x, y) {
+    return x + y;
+}
+
+//// Four slashes are a plain comment.
+fn plain() {}
+
+/// A point in the plane.
+class Point {
+    /// Creates a point.
+    init(x, y) {
+        self.x = x;
+        self.y = y;
+    }
+
+    length() {
+        return self.x + self.y;
+    }
+}
+
+/// Not attached to anything.
+
+var origin = Point(0, 0);
+";
+
+#[test]
+fn doc_comments_attach_to_the_following_declaration() {
+    let mut engine = Engine::new();
+    engine.eval(SOURCE).unwrap();
+
+    assert_eq!(
+        engine.documentation("add").as_deref(),
+        Some("Adds two numbers.\nReturns their sum.")
+    );
+    assert_eq!(
+        engine.documentation("Point").as_deref(),
+        Some("A point in the plane.")
+    );
+    assert_eq!(
+        engine.documentation("Point.init").as_deref(),
+        Some("Creates a point.")
+    );
+    assert_eq!(engine.documentation("Point.length"), None);
+    assert_eq!(engine.documentation("plain"), None);
+    assert_eq!(engine.documentation("origin"), None);
+}
+
+#[test]
+fn document_renders_markdown_for_functions_and_classes() {
+    let markdown = document("points.hez".to_string(), SOURCE.to_string()).unwrap();
+
+    assert_eq!(
+        markdown,
+        "# points.hez\n\n\
+         ## `add(x, y)`\n\nAdds two numbers.\nReturns their sum.\n\n\
+         ## `plain()`\n\n\
+         ## `class Point`\n\nA point in the plane.\n\n\
+         ### `Point.init(x, y)`\n\nCreates a point.\n\n\
+         ### `Point.length()`\n"
+    );
+}
+
+#[test]
+fn document_shows_destructured_parameters_as_their_patterns() {
+    let markdown = document(
+        "shapes.hez".to_string(),
+        "fn area((w, h), [first, ...rest], scale) {\n    var (a, b) = scale;\n}\n\
+         class Box {\n    fit([x, y]) {}\n}\n"
+            .to_string(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        markdown,
+        "# shapes.hez\n\n\
+         ## `area((w, h), [first, ...rest], scale)`\n\n\
+         ## `class Box`\n\n\
+         ### `Box.fit([x, y])`\n"
+    );
+}
@@ -0,0 +1,109 @@
+use hezen_interpreter::{shell_with, Engine, HezenValue, SharedBuffer};
+
+/// An engine whose stdout and stderr are captured.
+fn captured() -> (Engine, SharedBuffer, SharedBuffer) {
+    let stdout = SharedBuffer::new();
+    let stderr = SharedBuffer::new();
+
+    let mut engine = Engine::new();
+    engine.set_stdout(stdout.clone());
+    engine.set_stderr(stderr.clone());
+
+    (engine, stdout, stderr)
+}
+
+#[test]
+fn print_is_captured() {
+    let (mut engine, stdout, stderr) = captured();
+
+    engine
+        .eval("print(\"a\"); print(1); println(\"\"); println([1, \"b\"]);")
+        .unwrap();
+    assert_eq!(stdout.contents(), "a1\n[1, b]\n");
+
+    stdout.clear();
+    engine.eval("println(\"again\");").unwrap();
+    assert_eq!(stdout.contents(), "again\n");
+    assert_eq!(stderr.contents(), "");
+}
+
+#[test]
+fn warnings_are_collected() {
+    let (mut engine, stdout, stderr) = captured();
+
+    let result = engine.eval("match 2 { _ => println(\"any\"), 1 => println(\"one\") };");
+
+    assert!(result.is_ok());
+    assert_eq!(stdout.contents(), "any\n");
+    let warnings = engine.take_warnings();
+    let messages: Vec<&str> = warnings.iter().map(|warning| warning.message()).collect();
+    assert_eq!(messages, ["Unreachable match arm."]);
+    assert!(engine.take_warnings().is_empty());
+    assert_eq!(stderr.contents(), "");
+}
+
+#[test]
+fn shell_writes_output_to_stdout_and_problems_to_stderr() {
+    let (mut engine, stdout, stderr) = captured();
+
+    shell_with(
+        &mut engine,
+        "println(\"hello\");\nprintln(count);\nmatch 1 { _ => 0, 1 => 1 };\nexit\n".as_bytes(),
+    );
+
+    let stdout = stdout.contents();
+    let stderr = stderr.contents();
+    assert!(stdout.contains("> hello\n> "), "{stdout}");
+    assert!(!stdout.contains("count"), "{stdout}");
+    assert!(!stdout.contains("Unreachable"), "{stdout}");
+    assert!(stderr.contains("Undefined variable"), "{stderr}");
+    assert!(stderr.contains("count"), "{stderr}");
+    assert!(stderr.contains("Unreachable match arm."), "{stderr}");
+    assert!(!stderr.contains("hello"), "{stderr}");
+}
+
+#[test]
+fn shell_stops_when_the_script_exits() {
+    let (mut engine, stdout, _) = captured();
+
+    shell_with(&mut engine, "exit(4);\nprintln(\"after\");\n".as_bytes());
+
+    assert_eq!(engine.exit_code(), Some(4));
+    assert!(!stdout.contents().contains("after"));
+}
+
+#[test]
+fn globals_round_trip_through_eval_and_call() {
+    let (mut engine, stdout, _) = captured();
+    engine.set_global("base", 10);
+    engine.set_global("greeting", "hi");
+
+    engine
+        .eval(
+            "var total = base * 2;
+             fn add(n) { return total + n; }
+             fn greet(name) { println(greeting + \" \" + name); }",
+        )
+        .unwrap();
+
+    assert_eq!(engine.get::<i64>("total").unwrap(), 20);
+    assert_eq!(engine.get_global("total"), Some(HezenValue::Integer(20)));
+    assert_eq!(
+        engine.call("add", &[HezenValue::Integer(5)]).unwrap(),
+        HezenValue::Integer(25)
+    );
+    assert_eq!(engine.eval("add(2);").unwrap(), HezenValue::Integer(22));
+
+    engine
+        .call("greet", &[HezenValue::String("ada".to_string())])
+        .unwrap();
+    assert_eq!(stdout.contents(), "hi ada\n");
+
+    engine.set_global("base", 1);
+    assert_eq!(
+        engine.eval("base + total;").unwrap(),
+        HezenValue::Integer(21)
+    );
+    assert!(engine.call("missing", &[]).is_err());
+    assert!(engine.get::<i64>("greeting").is_err());
+}
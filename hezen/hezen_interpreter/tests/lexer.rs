@@ -0,0 +1,18 @@
+use hezen_interpreter::Engine;
+
+fn first_error(source: &str) -> (String, usize, usize) {
+    let errors = Engine::new().eval(source).unwrap_err();
+    let error = errors.iter().next().unwrap();
+    let info = error.info();
+    (error.message().to_string(), info.line, info.column)
+}
+
+#[test]
+fn invalid_escapes_are_reported_at_their_backslash() {
+    let (message, line, column) = first_error("\"ab\\qcd\";");
+    assert!(message.starts_with("Invalid escape sequence"), "{message}");
+    assert_eq!((line, column), (1, 4));
+
+    let (_, line, column) = first_error("var s = 1;\nvar t = \"x\\x4\";");
+    assert_eq!((line, column), (2, 11));
+}
@@ -0,0 +1,235 @@
+use std::time::{Duration, Instant};
+
+use hezen_interpreter::{Engine, HezenValue, Limits};
+
+fn first_kind(engine: &mut Engine, source: &str) -> String {
+    let errors = engine.eval(source).unwrap_err();
+    errors.iter().next().unwrap().kind().to_string()
+}
+
+#[test]
+fn timeout_cannot_be_caught_and_resumed() {
+    let mut engine = Engine::new();
+    engine.set_limits(Limits {
+        time: Some(Duration::from_millis(200)),
+        ..Limits::default()
+    });
+
+    let started = Instant::now();
+    let kind = first_kind(
+        &mut engine,
+        "var mut n = 0;
+         while n < 3000000 {
+             try { while true {} } catch e { n += 1; }
+         }",
+    );
+
+    assert_eq!(kind, "TimeoutError");
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn timeout_stops_calls_made_from_a_catch() {
+    let mut engine = Engine::new();
+    engine.set_limits(Limits {
+        time: Some(Duration::from_millis(100)),
+        ..Limits::default()
+    });
+    engine.eval("fn spin() { while true {} }").unwrap();
+
+    let kind = first_kind(&mut engine, "try { spin(); } catch e { spin(); }");
+
+    assert_eq!(kind, "TimeoutError");
+}
+
+#[test]
+fn next_run_gets_a_fresh_time_limit() {
+    let mut engine = Engine::new();
+    engine.set_limits(Limits {
+        time: Some(Duration::from_millis(50)),
+        ..Limits::default()
+    });
+
+    assert_eq!(first_kind(&mut engine, "while true {}"), "TimeoutError");
+    assert!(engine.eval("1 + 1;").is_ok());
+}
+
+#[test]
+fn instruction_limit_cannot_be_caught_and_resumed() {
+    let mut engine = Engine::new();
+    engine.set_limits(Limits {
+        instructions: Some(1000),
+        ..Limits::default()
+    });
+
+    let kind = first_kind(
+        &mut engine,
+        "var mut n = 0;
+         while true { try { while true {} } catch e { n += 1; } }",
+    );
+
+    assert_eq!(kind, "InstructionLimitError");
+}
+
+#[test]
+fn limits_are_reported_where_they_are_hit() {
+    let mut engine = Engine::new();
+    engine.set_limits(Limits {
+        instructions: Some(100),
+        call_depth: Some(10),
+        ..Limits::default()
+    });
+
+    let errors = engine.eval("var x = 1;\n\nwhile true {}").unwrap_err();
+    let info = errors.iter().next().unwrap().info();
+    assert_eq!((info.file.as_str(), info.line), ("<eval>", 3));
+
+    let errors = engine
+        .eval("fn down(n) {\n    return down(n + 1);\n}\ndown(0);")
+        .unwrap_err();
+    let error = errors.iter().next().unwrap();
+    assert_eq!(error.kind(), "CallDepthError");
+    assert_eq!(error.info().line, 2);
+}
+
+#[test]
+fn native_errors_are_reported_at_their_call() {
+    let mut engine = Engine::new();
+
+    let errors = engine
+        .eval("fn measure(value) {\n    return len(value);\n}\nmeasure(1);")
+        .unwrap_err();
+    let info = errors.iter().next().unwrap().info();
+    assert_eq!(
+        (info.file.as_str(), info.line, info.column),
+        ("<eval>", 2, 21)
+    );
+}
+
+fn engine_with_memory(bytes: usize) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_limits(Limits {
+        memory: Some(bytes),
+        ..Limits::default()
+    });
+    engine
+}
+
+#[test]
+fn natives_check_the_memory_limit_before_allocating() {
+    let mut engine = engine_with_memory(2 * 1024 * 1024);
+    engine.eval("var text = \"word \".repeat(20000);").unwrap();
+    engine
+        .eval("var numbers = \"[\" + \"0,\".repeat(100000) + \"0]\";")
+        .unwrap();
+
+    for source in [
+        "\"x\".repeat(300000000);",
+        "text.replace(\"\", text);",
+        "text.chars();",
+        "Regex(\"\").find_all(text);",
+        "Regex(\"w\").replace(text, text);",
+        "json.parse(numbers);",
+    ] {
+        let before = engine.memory_used();
+        assert_eq!(first_kind(&mut engine, source), "MemoryError", "{source}");
+        assert!(engine.memory_used() < before + 64 * 1024, "{source}");
+    }
+}
+
+#[test]
+fn files_larger_than_the_memory_limit_are_not_read() {
+    let path = std::env::temp_dir().join("hezen_limits_large_file.txt");
+    std::fs::write(&path, "line\n".repeat(100_000)).unwrap();
+    let path = path.to_str().unwrap();
+
+    let mut engine = engine_with_memory(64 * 1024);
+    assert_eq!(
+        first_kind(&mut engine, &format!("read_file(\"{path}\");")),
+        "MemoryError"
+    );
+    assert_eq!(
+        engine
+            .eval(&format!("read_lines(\"{path}\").next();"))
+            .unwrap(),
+        HezenValue::String("line".to_string())
+    );
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn calls_are_charged_for_their_scopes() {
+    let mut engine = engine_with_memory(64 * 1024);
+    engine
+        .eval("fn pair(first, second) { return nil; }")
+        .unwrap();
+
+    engine.eval("pair(1, 2);").unwrap();
+    let one_call = engine.memory_used();
+    assert!(one_call > 0);
+
+    assert_eq!(
+        first_kind(&mut engine, "while true { pair(1, 2); }"),
+        "MemoryError"
+    );
+}
+
+#[test]
+fn arithmetic_results_are_charged() {
+    let mut engine = engine_with_memory(64 * 1024);
+    assert_eq!(
+        first_kind(&mut engine, "var y = 7 ** 300000;"),
+        "MemoryError"
+    );
+    assert_eq!(
+        first_kind(&mut engine, "var mut x = 3; while true { x = x * x; }"),
+        "MemoryError"
+    );
+
+    engine.eval("var z = 2 ** 80000;").unwrap();
+    assert!(engine.memory_used() > 10000, "{}", engine.memory_used());
+
+    engine.eval("var n = 2 ** 10 + 1;").unwrap();
+    assert!(engine.memory_used() < 1000, "{}", engine.memory_used());
+}
+
+#[test]
+fn text_made_from_values_is_checked_as_it_grows() {
+    let mut engine = engine_with_memory(64 * 1024);
+    engine
+        .eval("var mut x = [1]; var mut i = 0; while i < 24 { x = [x, x]; i += 1; }")
+        .unwrap();
+
+    for source in ["json.stringify(x, nil);", "show(x);", "\"${x}\";"] {
+        let started = Instant::now();
+        assert_eq!(first_kind(&mut engine, source), "MemoryError", "{source}");
+        assert!(started.elapsed() < Duration::from_secs(1), "{source}");
+    }
+
+    assert_eq!(
+        engine.eval("json.stringify([1, \"a\"], nil);").unwrap(),
+        HezenValue::String("[1,\"a\"]".to_string())
+    );
+}
+
+#[test]
+fn cancelling_stops_natives_working_through_large_values() {
+    let mut engine = Engine::new();
+    engine
+        .eval("var mut x = [1]; var mut i = 0; while i < 24 { x = [x, x]; i += 1; }")
+        .unwrap();
+
+    for source in ["json.stringify(x, nil);", "show(x);", "\"${x}\";"] {
+        let token = engine.cancellation_token();
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            token.cancel();
+        });
+
+        let started = Instant::now();
+        assert_eq!(first_kind(&mut engine, source), "Interrupted", "{source}");
+        assert!(started.elapsed() < Duration::from_secs(2), "{source}");
+        canceller.join().unwrap();
+    }
+}
@@ -0,0 +1,38 @@
+use hezen_interpreter::{Engine, HezenValue};
+
+fn validation_messages(source: &str) -> Vec<String> {
+    let errors = Engine::new().eval(source).unwrap_err();
+    errors
+        .iter()
+        .filter(|error| error.kind() == "ValidationError")
+        .map(|error| error.message().to_string())
+        .collect()
+}
+
+#[test]
+fn alternatives_must_bind_the_same_names() {
+    assert_eq!(
+        validation_messages("match 1 { 1 | x => x };"),
+        ["Variable 'x' is not bound in all alternatives."]
+    );
+    assert_eq!(
+        validation_messages(
+            "enum E { A(a), B(b) }
+             match E.A(1) { E.A(a) | E.B(b) => 0 };"
+        ),
+        ["Variable 'a' is not bound in all alternatives."]
+    );
+}
+
+#[test]
+fn alternatives_binding_the_same_names_run() {
+    let mut engine = Engine::new();
+    let result = engine
+        .eval(
+            "enum E { A(v), B(v) }
+             match E.B(7) { E.A(v) | E.B(v) => v };",
+        )
+        .unwrap();
+
+    assert_eq!(result, HezenValue::Integer(7));
+}
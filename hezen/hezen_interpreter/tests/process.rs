@@ -0,0 +1,130 @@
+use hezen_core::{ErrorFormat, Outcome, Verbosity};
+use hezen_interpreter::{Capability, Engine, HezenValue, InterpreterBuilder, SharedBuffer};
+
+fn engine_with_args(args: &[&str]) -> Engine {
+    Engine::with_interpreter(
+        InterpreterBuilder::new()
+            .allow_all()
+            .args(args.iter().map(|arg| arg.to_string()).collect())
+            .build(),
+    )
+}
+
+fn quiet() -> Verbosity {
+    Verbosity {
+        lexer: false,
+        intermediate: false,
+        resolver: false,
+    }
+}
+
+#[test]
+fn args_are_given_per_interpreter() {
+    let mut first = engine_with_args(&["input.csv", "--verbose"]);
+    let mut second = engine_with_args(&[]);
+
+    assert_eq!(
+        first.eval("args();").unwrap().to_string(),
+        "[input.csv, --verbose]"
+    );
+    assert_eq!(
+        first.eval("args()[1];").unwrap(),
+        HezenValue::String("--verbose".to_string())
+    );
+    assert_eq!(second.eval("len(args());").unwrap(), HezenValue::Integer(0));
+}
+
+#[test]
+fn env_reads_variables() {
+    std::env::set_var("HEZEN_PROCESS_TEST_VALUE", "set by the test");
+    let mut engine = Engine::new();
+
+    assert_eq!(
+        engine.eval("env(\"HEZEN_PROCESS_TEST_VALUE\");").unwrap(),
+        HezenValue::String("set by the test".to_string())
+    );
+    assert_eq!(
+        engine
+            .eval("env(\"HEZEN_PROCESS_TEST_SURELY_UNSET\");")
+            .unwrap(),
+        HezenValue::Nil
+    );
+}
+
+#[test]
+fn exit_ends_the_run_with_its_code() {
+    let mut engine = Engine::new();
+    let stdout = SharedBuffer::new();
+    engine.set_stdout(stdout.clone());
+
+    engine
+        .eval("println(\"before\"); try { exit(3); } catch e { println(\"caught\"); } println(\"after\");")
+        .unwrap();
+
+    assert_eq!(engine.exit_code(), Some(3));
+    assert_eq!(stdout.contents(), "before\n");
+}
+
+#[test]
+fn exit_from_a_called_function_ends_the_call() {
+    let mut engine = Engine::new();
+    engine
+        .eval("fn stop(code) { exit(code); return 1; }")
+        .unwrap();
+
+    assert_eq!(
+        engine.call("stop", &[HezenValue::Integer(4)]).unwrap(),
+        HezenValue::Nil
+    );
+    assert_eq!(engine.exit_code(), Some(4));
+
+    engine.eval("1;").unwrap();
+    assert_eq!(engine.exit_code(), None);
+}
+
+#[test]
+fn exit_codes_must_fit_in_an_i32() {
+    let mut engine = engine_with_args(&[]);
+
+    for (code, type_name) in [
+        ("\"1\"", "string"),
+        ("3000000000", "integer"),
+        ("1.5", "number"),
+    ] {
+        let errors = engine.eval(&format!("exit({code});")).unwrap_err();
+        assert_eq!(
+            errors.iter().next().unwrap().message(),
+            format!(
+                "Expected argument 1 of 'exit' to be an integer that fits in an i32, not '{type_name}'"
+            )
+        );
+        assert_eq!(engine.exit_code(), None);
+    }
+}
+
+#[test]
+fn exit_is_unavailable_without_its_capability() {
+    let mut engine =
+        Engine::with_interpreter(InterpreterBuilder::new().allow(Capability::Print).build());
+
+    assert!(engine.eval("exit(1);").is_err());
+    assert_eq!(engine.exit_code(), None);
+}
+
+#[test]
+fn run_reports_the_exit_code_as_its_outcome() {
+    let outcome = |code: &str| {
+        hezen_interpreter::run(
+            "main.hez".to_string(),
+            code.to_string(),
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            quiet(),
+            ErrorFormat::Human,
+        )
+    };
+
+    assert_eq!(outcome("var x = 1;").unwrap(), Outcome::Finished);
+    assert_eq!(outcome("exit(len(args()));").unwrap(), Outcome::Exited(3));
+    assert_eq!(outcome("exit(0);").unwrap().exit_code(), 0);
+    assert_eq!(outcome("1 / nil;").unwrap_err().exit_code(), 70);
+}
@@ -1,5 +1,5 @@
 use hezen_core::error::HezenErrorList;
-use hezen_core::Verbosity;
+use hezen_core::{ErrorFormat, Outcome, Verbosity};
 
 #[cfg(all(feature = "interpreter", feature = "compiler"))]
 compile_error!(
@@ -7,8 +7,14 @@ compile_error!(
 );
 
 #[cfg(feature = "interpreter")]
-pub fn run(filename: String, code: String, verbosity: Verbosity) -> Result<(), HezenErrorList> {
-    hezen_interpreter::run(filename, code, verbosity)
+pub fn run(
+    filename: String,
+    code: String,
+    args: Vec<String>,
+    verbosity: Verbosity,
+    error_format: ErrorFormat,
+) -> Result<Outcome, HezenErrorList> {
+    hezen_interpreter::run(filename, code, args, verbosity, error_format)
 }
 
 #[cfg(feature = "interpreter")]
@@ -16,12 +22,35 @@ pub fn shell() {
     hezen_interpreter::shell()
 }
 
+#[cfg(feature = "interpreter")]
+pub fn document(filename: String, code: String) -> Result<String, HezenErrorList> {
+    hezen_interpreter::document(filename, code)
+}
+
 #[cfg(feature = "compiler")]
-pub fn run(filename: String, code: String, verbosity: Verbosity) -> Result<(), HezenErrorList> {
-    hezen_compiler::run(filename, code, verbosity)
+pub fn run(
+    filename: String,
+    code: String,
+    _args: Vec<String>,
+    verbosity: Verbosity,
+    _error_format: ErrorFormat,
+) -> Result<Outcome, HezenErrorList> {
+    hezen_compiler::run(filename, code, verbosity).map(|_| Outcome::Finished)
 }
 
 #[cfg(feature = "compiler")]
 pub fn shell() {
     unimplemented!()
 }
+
+#[cfg(feature = "compiler")]
+pub fn document(filename: String, _code: String) -> Result<String, HezenErrorList> {
+    Err(HezenErrorList::from(
+        hezen_core::error::HezenError::validation(
+            filename,
+            0,
+            0,
+            "Documentation is not supported by the compiler backend".to_string(),
+        ),
+    ))
+}